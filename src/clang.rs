@@ -37,6 +37,11 @@ impl Cursor {
         unsafe { clang_getCursorExtent(self.x) }
     }
 
+    /// The location of the last token of the cursor.
+    pub fn extent_end(&self) -> SourceLocation {
+        unsafe { SourceLocation { x: clang_getRangeEnd(self.extent()) } }
+    }
+
     pub fn cur_type(&self) -> Type {
         unsafe { Type { x: clang_getCursorType(self.x) } }
     }
@@ -45,6 +50,16 @@ impl Cursor {
         unsafe { Cursor { x: clang_getCursorDefinition(self.x) } }
     }
 
+    /// Whether this base class specifier is `virtual`.
+    pub fn is_virtual_base(&self) -> bool {
+        unsafe { clang_isVirtualBase(self.x) != 0 }
+    }
+
+    /// The template this cursor specializes or instantiates, if any.
+    pub fn specialized_template(&self) -> Cursor {
+        unsafe { Cursor { x: clang_getSpecializedCursorTemplate(self.x) } }
+    }
+
     pub fn canonical(&self) -> Cursor {
         unsafe { Cursor { x: clang_getCanonicalCursor(self.x) } }
     }
@@ -79,6 +94,10 @@ impl Cursor {
         unsafe { clang_getEnumConstantDeclValue(self.x) as i64 }
     }
 
+    pub fn enum_unsigned_val(&self) -> u64 {
+        unsafe { clang_getEnumConstantDeclUnsignedValue(self.x) as u64 }
+    }
+
     // typedef
     pub fn typedef_type(&self) -> Type {
         unsafe { Type { x: clang_getTypedefDeclUnderlyingType(self.x) } }
@@ -89,6 +108,12 @@ impl Cursor {
         unsafe { clang_getCursorLinkage(self.x) }
     }
 
+    /// The documentation comment attached to the declaration, with its
+    /// delimiters, empty without one.
+    pub fn raw_comment(&self) -> String {
+        unsafe { String_ { x: clang_Cursor_getRawCommentText(self.x) }.to_string() }
+    }
+
     // function
     pub fn args(&self) -> Vec<Cursor> {
         unsafe {
@@ -108,6 +133,24 @@ impl Cursor {
     pub fn num_args(&self) -> i32 {
         unsafe { clang_Cursor_getNumArguments(self.x) }
     }
+
+    /// The symbol the declaration is mangled to.
+    pub fn mangling(&self) -> String {
+        unsafe { String_ { x: clang_Cursor_getMangling(self.x) }.to_string() }
+    }
+
+    // method
+    pub fn is_const_method(&self) -> bool {
+        unsafe { clang_CXXMethod_isConst(self.x) != 0 }
+    }
+
+    pub fn is_static_method(&self) -> bool {
+        unsafe { clang_CXXMethod_isStatic(self.x) != 0 }
+    }
+
+    pub fn is_pure_virtual_method(&self) -> bool {
+        unsafe { clang_CXXMethod_isPureVirtual(self.x) != 0 }
+    }
 }
 
 extern "C" fn visit_children(cur: CXCursor,
@@ -173,6 +216,10 @@ impl Type {
         unsafe { clang_isConstQualifiedType(self.x) == 1 }
     }
 
+    pub fn is_volatile(&self) -> bool {
+        unsafe { clang_isVolatileQualifiedType(self.x) == 1 }
+    }
+
     pub fn size(&self) -> usize {
         unsafe {
             let val = clang_Type_getSizeOf(self.x);
@@ -195,6 +242,19 @@ impl Type {
         }
     }
 
+    /// The offset in bytes of the field named `field` in this record type.
+    pub fn offset_of(&self, field: &str) -> Option<usize> {
+        let name = CString::new(field.as_bytes()).unwrap();
+        unsafe {
+            let val = clang_Type_getOffsetOf(self.x, name.as_ptr());
+            if val < 0 {
+                None
+            } else {
+                Some(val as usize / 8)
+            }
+        }
+    }
+
     // pointer
     pub fn pointee_type(&self) -> Type {
         unsafe { Type { x: clang_getPointeeType(self.x) } }
@@ -325,6 +385,7 @@ impl Index {
 pub struct Token {
     pub kind: CXTokenKind,
     pub spelling: String,
+    pub location: SourceLocation,
 }
 
 // TranslationUnit
@@ -395,7 +456,24 @@ impl TranslationUnit {
     }
 
     pub fn tokens(&self, cursor: &Cursor) -> Option<Vec<Token>> {
-        let range = cursor.extent();
+        self.tokenize(cursor.extent())
+    }
+
+    /// The tokens between two `(line, column)` positions of a file.
+    pub fn file_tokens(&self,
+                       file: &File,
+                       start: (usize, usize),
+                       end: (usize, usize))
+                       -> Option<Vec<Token>> {
+        let range = unsafe {
+            let start = clang_getLocation(self.x, file.x, start.0 as c_uint, start.1 as c_uint);
+            let end = clang_getLocation(self.x, file.x, end.0 as c_uint, end.1 as c_uint);
+            clang_getRange(start, end)
+        };
+        self.tokenize(range)
+    }
+
+    fn tokenize(&self, range: CXSourceRange) -> Option<Vec<Token>> {
         let mut tokens = vec![];
         unsafe {
             let mut token_ptr = ::std::ptr::null_mut();
@@ -408,9 +486,11 @@ impl TranslationUnit {
             for &token in token_array.iter() {
                 let kind = clang_getTokenKind(token);
                 let spelling = String_ { x: clang_getTokenSpelling(self.x, token) }.to_string();
+                let location = SourceLocation { x: clang_getTokenLocation(self.x, token) };
                 tokens.push(Token {
                     kind: kind,
                     spelling: spelling,
+                    location: location,
                 });
             }
             clang_disposeTokens(self.x, token_ptr, num_tokens);
@@ -437,6 +517,21 @@ impl Diagnostic {
         unsafe { clang_getDiagnosticSeverity(self.x) }
     }
 
+    pub fn spelling(&self) -> String {
+        unsafe { String_ { x: clang_getDiagnosticSpelling(self.x) }.to_string() }
+    }
+
+    pub fn location(&self) -> SourceLocation {
+        unsafe { SourceLocation { x: clang_getDiagnosticLocation(self.x) } }
+    }
+
+    /// The command line option enabling the diagnostic, such as `-Wunused`.
+    pub fn option(&self) -> String {
+        unsafe {
+            String_ { x: clang_getDiagnosticOption(self.x, ::std::ptr::null_mut()) }.to_string()
+        }
+    }
+
     pub fn dispose(&self) {
         unsafe {
             clang_disposeDiagnostic(self.x);
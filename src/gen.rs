@@ -1,9 +1,10 @@
 use std;
+use std::ascii;
 use std::mem;
 use std::cell::RefCell;
 use std::vec::Vec;
 use std::rc::Rc;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use syntax::abi;
 use syntax::ast;
@@ -13,19 +14,110 @@ use syntax::ext::build::AstBuilder;
 use syntax::ext::expand::ExpansionConfig;
 use syntax::ext::quote::rt::ToTokens;
 use syntax::feature_gate::Features;
+use syntax::fold::{self, Folder};
 use syntax::parse;
 use syntax::parse::token::InternedString;
-use syntax::attr::mk_attr_id;
+use syntax::attr::{AttrMetaMethods, mk_attr_id};
 use syntax::ptr::P;
+use syntax::print::pprust;
 use syntax::print::pprust::tts_to_string;
 
-use super::{BindgenOptions, LinkType};
+use regex::RegexSet;
+
+use super::{BindgenError, BindgenOptions, LinkType, Logger, Visibility};
+use parser::{compile_whitelist, matches};
 use types::*;
 
 struct GenCtx<'r> {
     ext_cx: base::ExtCtxt<'r>,
+    logger: &'r (Logger + 'r),
     unnamed_ty: usize,
     span: Span,
+    c_naming: bool,
+    float16_as_f16: bool,
+    uses_float16: bool,
+    raw_link_names: bool,
+    original_name_comments: bool,
+    /// Whether to turn the C documentation comments into doc attributes.
+    comments: bool,
+    /// The crate to take the `Option`, `Default`, C types etc. from, `std` or
+    /// `core`.
+    std_crate: &'static str,
+    /// The module holding the generated helper types, if any.
+    helpers_module: Option<String>,
+    int_literal_suffixes: bool,
+    /// Whether to emit the C integer types with their width on the target,
+    /// like `i64` for `long`, instead of `c_long`.
+    fixed_width_integers: bool,
+    /// Whether to translate the `noreturn`, `pure` and `const` attributes of
+    /// the functions.
+    function_attributes: bool,
+    /// Whether to document the `pure` and `const` attributes, which have no
+    /// Rust equivalent.
+    function_attribute_docs: bool,
+    /// The prefix of the exported symbols of the functions and variables.
+    link_prefix: String,
+    /// The prefix to remove from the names of the functions and variables.
+    strip_prefix: String,
+    /// Whether the C types and `Option` are imported by a prelude, and can be
+    /// named without their path.
+    prelude: bool,
+    /// Whether the renamed items get their C name as a `doc(alias)`.
+    doc_alias: bool,
+    /// Whether the generated wrappers that can panic are `#[track_caller]`.
+    track_caller: bool,
+    patterns: NamePatterns,
+    /// The paths the initializers implementing `Default` are called by, by
+    /// struct name.
+    initializers: HashMap<String, String>,
+}
+
+/// The option lists matching the names of the items, compiled like the
+/// whitelists.
+pub struct NamePatterns {
+    blocklist: Option<RegexSet>,
+    opaque_types: Option<RegexSet>,
+    bitfield_enums: Option<RegexSet>,
+    /// The traits to derive on the structs.
+    derives: Option<RegexSet>,
+    cstr_fields: Option<RegexSet>,
+    /// The patterns of `flag_constant_types`, in the same order.
+    flag_constant_types: Option<RegexSet>,
+}
+
+/// The traits `Builder::derive` can add.
+const DERIVABLE_TRAITS: [&'static str; 4] = ["PartialEq", "Eq", "Hash", "Default"];
+
+impl NamePatterns {
+    pub fn new(options: &BindgenOptions, logger: &Logger) -> Result<NamePatterns, BindgenError> {
+        let flag_enums: Vec<_> = options.flag_constant_types
+                                        .iter()
+                                        .map(|&(ref pattern, _)| pattern.clone())
+                                        .collect();
+        let derives = try!(compile_whitelist(logger, &options.derives));
+        if let Some(ref set) = derives {
+            let mut used = vec![false; options.derives.len()];
+            for trait_name in &DERIVABLE_TRAITS {
+                for i in set.matches(trait_name).iter() {
+                    used[i] = true;
+                }
+            }
+            for (pattern, _) in options.derives.iter().zip(used).filter(|&(_, u)| !u) {
+                let msg = format!("`{}` matches none of the traits which can be derived, {}",
+                                  pattern,
+                                  DERIVABLE_TRAITS.join(", "));
+                logger.warn(&msg[..]);
+            }
+        }
+        Ok(NamePatterns {
+            blocklist: try!(compile_whitelist(logger, &options.blocklist_item)),
+            opaque_types: try!(compile_whitelist(logger, &options.opaque_types)),
+            bitfield_enums: try!(compile_whitelist(logger, &options.bitfield_enums)),
+            derives: derives,
+            cstr_fields: try!(compile_whitelist(logger, &options.cstr_fields)),
+            flag_constant_types: try!(compile_whitelist(logger, &flag_enums)),
+        })
+    }
 }
 
 fn ref_eq<T>(thing: &T, other: &T) -> bool {
@@ -34,7 +126,7 @@ fn ref_eq<T>(thing: &T, other: &T) -> bool {
 
 fn rust_id(ctx: &mut GenCtx, name: &str) -> (String, bool) {
     let token = parse::token::Ident(ctx.ext_cx.ident_of(name));
-    if token.is_any_keyword() || "bool" == name {
+    if token.is_any_keyword() || (!ctx.c_naming && "bool" == name) {
         let s = format!("_{}", name);
         (s, true)
     } else {
@@ -43,6 +135,9 @@ fn rust_id(ctx: &mut GenCtx, name: &str) -> (String, bool) {
 }
 
 fn rust_type_id(ctx: &mut GenCtx, name: &str) -> String {
+    if ctx.c_naming {
+        return rust_id(ctx, name).0;
+    }
     match name {
         "bool" |
         "uint" |
@@ -116,7 +211,9 @@ fn extract_definitions(ctx: &mut GenCtx,
         match *g {
             GType(ref ti) => {
                 let t = ti.borrow();
-                defs.extend(ctypedef_to_rs(ctx, options, options.derive_debug, &t.name, &t.ty))
+                let mut items = ctypedef_to_rs(ctx, options, options.derive_debug, &t.name, &t.ty);
+                add_doc_attrs(ctx, &mut items, &t.comment);
+                defs.extend(items.into_iter())
             }
             GCompDecl(ref ci) => {
                 {
@@ -132,14 +229,16 @@ fn extract_definitions(ctx: &mut GenCtx,
                     c.name = unnamed_name(ctx, &c.name);
                 }
                 let c = ci.borrow().clone();
-                defs.extend(comp_to_rs(ctx,
-                                       c.kind,
-                                       comp_name(c.kind, &c.name),
-                                       options,
-                                       options.derive_debug,
-                                       c.layout,
-                                       c.members)
-                                .into_iter())
+                let mut items = comp_to_rs(ctx,
+                                           c.kind,
+                                           comp_name(c.kind, &c.name),
+                                           options,
+                                           options.derive_debug,
+                                           c.layout,
+                                           c.opaque,
+                                           c.members);
+                add_doc_attrs(ctx, &mut items, &c.comment);
+                defs.extend(items.into_iter())
             }
             GEnumDecl(ref ei) => {
                 {
@@ -155,18 +254,25 @@ fn extract_definitions(ctx: &mut GenCtx,
                     e.name = unnamed_name(ctx, &e.name);
                 }
                 let e = ei.borrow();
-                defs.extend(cenum_to_rs(ctx,
-                                        options,
-                                        options.derive_debug,
-                                        &enum_name(&e.name),
-                                        e.kind,
-                                        e.layout,
-                                        &e.items));
+                let mut items = cenum_to_rs(ctx,
+                                            options,
+                                            options.derive_debug,
+                                            &enum_name(&e.name),
+                                            e.kind,
+                                            e.layout,
+                                            &e.items);
+                add_doc_attrs(ctx, &mut items, &e.comment);
+                defs.extend(items.into_iter());
             }
             GVar(ref vi) => {
                 let v = vi.borrow();
                 let ty = cty_to_rs(ctx, &v.ty);
-                defs.push(const_to_rs(ctx, &v.name, v.val.unwrap(), ty));
+                let suffix = match v.ty {
+                    TInt(IBool, _) => ast::LitIntType::Unsuffixed,
+                    TInt(kind, layout) => int_lit_suffix(ctx, kind.is_signed(), layout.size),
+                    _ => ast::LitIntType::Unsuffixed,
+                };
+                defs.push(const_to_rs(ctx, &v.name, v.val.unwrap(), suffix, ty));
             }
             _ => {}
         }
@@ -182,11 +288,16 @@ fn extract_functions(ctx: &mut GenCtx, fs: &[Global]) -> HashMap<abi::Abi, Vec<a
                 let v = vi.borrow();
                 match v.ty {
                     TFuncPtr(ref sig, _) => {
-                        let decl = cfunc_to_rs(ctx,
-                                               v.name.clone(),
-                                               &*sig.ret_ty,
-                                               &sig.args[..],
-                                               sig.is_variadic);
+                        let mut decl = cfunc_to_rs(ctx,
+                                                   v.name.clone(),
+                                                   v.mangled.as_ref().map(|m| &m[..]),
+                                                   &*sig.ret_ty,
+                                                   &sig.args[..],
+                                                   sig.is_variadic);
+                        decl.attrs.extend(doc_attrs(ctx, &v.comment).into_iter());
+                        if ctx.function_attributes {
+                            add_function_attributes(ctx, &mut decl, &v);
+                        }
                         (sig.abi, decl)
                     }
                     _ => unreachable!(),
@@ -203,7 +314,44 @@ fn extract_functions(ctx: &mut GenCtx, fs: &[Global]) -> HashMap<abi::Abi, Vec<a
     map
 }
 
-/// Converts `typedef struct {...} Test` to rust `struct Test {...}`
+/// The paths the associated initializers are called by, next to the functions
+/// they are generated as. The ones which aren't generated as functions, being
+/// filtered out or only loaded at runtime, are left out with a warning.
+fn initializer_paths(ctx: &mut GenCtx,
+                     options: &BindgenOptions,
+                     fs: &[Global])
+                     -> HashMap<String, String> {
+    let mut paths = HashMap::new();
+    for &(ref ty, ref init) in &options.initializers {
+        let generated = fs.iter().any(|f| f.varinfo().borrow().name == *init);
+        if !generated || options.dynamic_library_runtime {
+            let msg = format!("the initializer `{}` of `{}` isn't generated as a function, \
+                               `Default` zeroes the struct instead",
+                              init,
+                              ty);
+            ctx.logger.warn(&msg[..]);
+            continue;
+        }
+        let module = options.function_prefix_modules
+                            .iter()
+                            .find(|&&(ref prefix, _)| init.starts_with(&prefix[..]));
+        let path = match module {
+            Some(&(ref prefix, ref module)) => {
+                format!("{}::{}", module, rust_id(ctx, strip_name(prefix, init)).0)
+            }
+            None => {
+                let stripped = strip_name(&ctx.strip_prefix, init).to_owned();
+                rust_id(ctx, &stripped).0
+            }
+        };
+        paths.insert(ty.clone(), path);
+    }
+    paths
+}
+
+/// Converts `typedef struct {...} Test` to rust `struct Test {...}`. With
+/// `typedef struct {...} A, B`, the struct is named `A` and `B` is kept as an
+/// alias of it.
 fn remove_unnamed(globals: &mut Vec<Global>) {
     let mut i = 1;
     while i < globals.len() {
@@ -216,6 +364,9 @@ fn remove_unnamed(globals: &mut Vec<Global>) {
                         let mut c = c.borrow_mut();
                         if c.name.is_empty() {
                             c.name = t.name.clone();
+                            if c.comment.is_empty() {
+                                c.comment = t.comment.clone();
+                            }
                             remove = true;
                         } else if c.name == t.name {
                             remove = true;
@@ -225,6 +376,9 @@ fn remove_unnamed(globals: &mut Vec<Global>) {
                         let mut e = e.borrow_mut();
                         if e.name.is_empty() {
                             e.name = t.name.clone();
+                            if e.comment.is_empty() {
+                                e.comment = t.comment.clone();
+                            }
                             remove = true;
                         } else if e.name == t.name {
                             remove = true;
@@ -245,7 +399,9 @@ fn remove_unnamed(globals: &mut Vec<Global>) {
 }
 
 pub fn gen_mod(options: &BindgenOptions,
+               patterns: NamePatterns,
                globs: Vec<Global>,
+               logger: &Logger,
                span: Span)
                -> (Vec<P<ast::Item>>, Vec<ast::Attribute>) {
     // Create a dummy ExtCtxt. We only need this for string interning and that uses TLS.
@@ -261,8 +417,28 @@ pub fn gen_mod(options: &BindgenOptions,
     let mut feature_gated_cfgs = Vec::new();
     let mut ctx = GenCtx {
         ext_cx: base::ExtCtxt::new(sess, Vec::new(), cfg, &mut feature_gated_cfgs),
+        logger: logger,
         unnamed_ty: 0,
         span: span,
+        c_naming: options.c_naming,
+        float16_as_f16: options.float16_as_f16,
+        uses_float16: false,
+        raw_link_names: options.raw_link_names,
+        original_name_comments: options.emit_original_name_comment && !options.strip_comments,
+        comments: options.generate_comments && !options.strip_comments,
+        std_crate: if options.use_core_ffi { "core" } else { "std" },
+        helpers_module: options.helpers_module.clone(),
+        int_literal_suffixes: options.enum_discriminant_type_suffix,
+        fixed_width_integers: options.fixed_width_integers,
+        function_attributes: options.emit_function_attributes,
+        function_attribute_docs: !options.strip_comments,
+        link_prefix: options.link_prefix.clone(),
+        strip_prefix: options.strip_prefix.clone(),
+        prelude: options.emit_prelude,
+        doc_alias: options.emit_doc_alias,
+        track_caller: options.emit_track_caller,
+        patterns: patterns,
+        initializers: HashMap::new(),
     };
     ctx.ext_cx.bt_push(ExpnInfo {
         call_site: ctx.span,
@@ -272,15 +448,23 @@ pub fn gen_mod(options: &BindgenOptions,
             span: None,
         },
     });
-    let uniq_globs = tag_dup_decl(&globs);
+    let mut uniq_globs = tag_dup_decl(&globs);
+    if ctx.patterns.blocklist.is_some() {
+        uniq_globs = remove_blocklisted(&mut ctx, uniq_globs);
+    }
+    uniq_globs.retain(|g| emits_kind(options, g));
 
     let mut fs = vec![];
     let mut vs = vec![];
     let mut gs = vec![];
+    let mut aliases = vec![];
+    let mut macros = vec![];
     for g in uniq_globs.into_iter() {
         match g {
             GOther => {}
             GFunc(_) => fs.push(g),
+            GAlias(..) => aliases.push(g),
+            GMacro(..) => macros.push(g),
             GVar(_) => {
                 let is_int_const = {
                     match g {
@@ -303,41 +487,496 @@ pub fn gen_mod(options: &BindgenOptions,
 
     gs = remove_redundant_decl(gs);
     remove_unnamed(&mut gs);
+    ctx.initializers = initializer_paths(&mut ctx, options, &fs);
     let mut defs = extract_definitions(&mut ctx, options, &gs);
+    defs.extend(mk_macro_consts(&mut ctx, &macros).into_iter());
+    let mut fn_groups = vec![];
+    if !options.dynamic_library_runtime {
+        for &(ref prefix, ref module) in &options.function_prefix_modules {
+            let (grouped, rest): (Vec<Global>, Vec<Global>) =
+                fs.into_iter().partition(|f| f.varinfo().borrow().name.starts_with(&prefix[..]));
+            fs = rest;
+            if !grouped.is_empty() {
+                fn_groups.push((prefix, module, grouped));
+            }
+        }
+    }
+    let (type_names, value_names) = generated_names(options, &gs, &vs, &fs);
+    let mut alias_defs = mk_macro_aliases(&mut ctx, &aliases, &type_names, &value_names);
+    if !options.trim_prefix.is_empty() && options.trim_prefix_reexport {
+        alias_defs.extend(mk_prefix_reexports(&mut ctx,
+                                              &options.trim_prefix,
+                                              &type_names,
+                                              &value_names)
+                              .into_iter());
+    }
+
+    if options.volatile_accessors {
+        for v in &vs {
+            if let GVar(ref vi) = *v {
+                defs.extend(mk_volatile_var_accessors(&mut ctx, &vi.borrow()).into_iter());
+            }
+        }
+    }
 
     let vars = vs.into_iter()
                  .map(|v| {
                      match v {
                          GVar(vi) => {
                              let v = vi.borrow();
-                             cvar_to_rs(&mut ctx, v.name.clone(), &v.ty, v.is_const)
+                             let mut var = cvar_to_rs(&mut ctx, v.name.clone(), &v.ty, v.is_const);
+                             var.attrs.extend(doc_attrs(&mut ctx, &v.comment).into_iter());
+                             var
                          }
                          _ => unreachable!(),
                      }
                  })
                  .collect();
 
-    let funcs = extract_functions(&mut ctx, &fs);
-
     if !Vec::is_empty(&vars) {
         defs.push(mk_extern(&mut ctx, &options.links, vars, abi::Abi::C));
     }
 
-    for (abi, funcs) in funcs.into_iter() {
-        defs.push(mk_extern(&mut ctx, &options.links, funcs, abi));
+    if options.dynamic_library_runtime {
+        if !fs.is_empty() {
+            defs.extend(mk_dynamic_library(&mut ctx, &fs).into_iter());
+        }
+    } else {
+        let funcs = extract_functions(&mut ctx, &fs);
+
+        for (abi, mut funcs) in funcs.into_iter() {
+            if options.sort_functions_by_name {
+                funcs.sort_by(|a, b| a.ident.name.as_str().cmp(&b.ident.name.as_str()));
+            }
+            defs.push(mk_extern(&mut ctx, &options.links, funcs, abi));
+        }
     }
 
-    // let attrs = vec!(mk_attr_list(&mut ctx, "allow", ));
-    let mod_attrs = vec![mk_attr_style(&mut ctx,
-                                       "allow",
-                                       &["dead_code",
-                                         "non_camel_case_types",
-                                         "non_upper_case_globals",
-                                         "non_snake_case"],
-                                       ast::AttrStyle::Inner)];
+    for (prefix, module, grouped) in fn_groups.into_iter() {
+        // The names are trimmed like with `strip_prefix`, keeping the symbol.
+        let strip_prefix = mem::replace(&mut ctx.strip_prefix, prefix.clone());
+        let funcs = extract_functions(&mut ctx, &grouped);
+        ctx.strip_prefix = strip_prefix;
+
+        let mut items = vec![];
+        for (abi, mut funcs) in funcs.into_iter() {
+            if options.sort_functions_by_name {
+                funcs.sort_by(|a, b| a.ident.name.as_str().cmp(&b.ident.name.as_str()));
+            }
+            items.push(mk_extern(&mut ctx, &options.links, funcs, abi));
+        }
+        defs.push(mk_fn_module(&mut ctx, module, items));
+    }
+
+    defs.extend(alias_defs.into_iter());
+
+    if !options.trim_prefix.is_empty() && !options.trim_prefix_reexport {
+        defs = trim_prefixes(&mut ctx, &options.trim_prefix, &type_names, &value_names, defs);
+    }
+
+    if options.emit_prelude {
+        defs = mk_prelude(&mut ctx).into_iter().chain(defs.into_iter()).collect();
+    }
+
+    let mut helpers = vec![];
+    if ctx.uses_float16 {
+        helpers.push(mk_float16_struct(&mut ctx));
+    }
+    if !helpers.is_empty() {
+        if let Some(ref module) = options.helpers_module {
+            helpers = vec![mk_helpers_mod(&mut ctx, module, helpers)];
+        }
+        defs = helpers.into_iter().chain(defs.into_iter()).collect();
+    }
+
+    if options.allow_dead_code_per_item {
+        let attr = mk_attr(&mut ctx, "allow", &["dead_code"]);
+        defs = defs.into_iter()
+                   .map(|item| {
+                       item.map(|mut item| {
+                           item.attrs.insert(0, attr.clone());
+                           item
+                       })
+                   })
+                   .collect();
+    }
+
+    let mut lints = vec![];
+    if options.allow_dead_code {
+        lints.push("dead_code");
+    }
+    lints.extend(&["non_camel_case_types", "non_upper_case_globals", "non_snake_case"]);
+    let mut mod_attrs = vec![];
+    if options.emit_crate_attributes && options.use_core_ffi {
+        let no_std = ctx.ext_cx.meta_word(ctx.span, InternedString::new("no_std"));
+        mod_attrs.push(respan(ctx.span,
+                              ast::Attribute_ {
+                                  id: mk_attr_id(),
+                                  style: ast::AttrStyle::Inner,
+                                  value: no_std,
+                                  is_sugared_doc: false,
+                              }));
+    }
+    mod_attrs.push(mk_attr_style(&mut ctx, "allow", &lints, ast::AttrStyle::Inner));
     (defs, mod_attrs)
 }
 
+/// Returns the C names of the generated types, and of the generated functions
+/// and variables, in declaration order.
+fn generated_names(options: &BindgenOptions,
+                   gs: &[Global],
+                   vs: &[Global],
+                   fs: &[Global])
+                   -> (Vec<String>, Vec<String>) {
+    let mut types = vec![];
+    let mut values = vec![];
+    for g in gs {
+        match *g {
+            GType(ref ti) => types.push(ti.borrow().name.clone()),
+            GComp(ref ci) |
+            GCompDecl(ref ci) => {
+                let c = ci.borrow();
+                types.push(comp_name(c.kind, &c.name));
+            }
+            GEnum(ref ei) |
+            GEnumDecl(ref ei) => types.push(enum_name(&ei.borrow().name)),
+            GVar(ref vi) => values.push(vi.borrow().name.clone()),
+            _ => {}
+        }
+    }
+    for g in vs {
+        values.push(strip_name(&options.strip_prefix, &g.varinfo().borrow().name).to_owned());
+    }
+    // The functions are only reachable through the library struct otherwise.
+    if !options.dynamic_library_runtime {
+        for g in fs {
+            values.push(strip_name(&options.strip_prefix, &g.varinfo().borrow().name).to_owned());
+        }
+    }
+    (types, values)
+}
+
+/// Converts `#define foo bar` to `pub use self::bar as foo;` when `bar` is a
+/// generated function or variable, and to `pub type foo = bar;` when it is a
+/// generated type.
+fn mk_macro_aliases(ctx: &mut GenCtx,
+                    aliases: &[Global],
+                    types: &[String],
+                    values: &[String])
+                    -> Vec<P<ast::Item>> {
+    let mut items = vec![];
+    for g in aliases {
+        let (name, target) = match *g {
+            GAlias(ref name, ref target) => (name, target),
+            _ => unreachable!(),
+        };
+        let item_str = if values.contains(target) {
+            format!("pub use self::{} as {};",
+                    rust_id(ctx, target).0,
+                    rust_id(ctx, name).0)
+        } else if types.contains(target) {
+            format!("pub type {} = {};",
+                    rust_type_id(ctx, name),
+                    rust_type_id(ctx, target))
+        } else {
+            let msg = format!("`{}` aliases `{}` which is not generated, skipping", name, target);
+            ctx.logger.warn(&msg[..]);
+            continue;
+        };
+
+        items.push(parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                     ctx.ext_cx.cfg(),
+                                                     "".to_owned(),
+                                                     item_str)
+                       .parse_item()
+                       .unwrap()
+                       .unwrap());
+    }
+    items
+}
+
+/// Converts the object-like macros expanding to a literal to constants.
+fn mk_macro_consts(ctx: &mut GenCtx, macros: &[Global]) -> Vec<P<ast::Item>> {
+    let raw = if ctx.prelude {
+        ""
+    } else if ctx.std_crate == "core" {
+        "::core::ffi::"
+    } else {
+        "::std::os::raw::"
+    };
+
+    macros.iter()
+          .map(|g| {
+              let (name, value) = match *g {
+                  GMacro(ref name, ref value) => (name, value),
+                  _ => unreachable!(),
+              };
+              let id = rust_id(ctx, name).0;
+              let item_str = match *value {
+                  MacroValue::Int(i) if i <= std::i32::MAX as i64 => {
+                      format!("pub const {}: {}c_int = {};", id, raw, i)
+                  }
+                  MacroValue::Int(i) if i <= std::u32::MAX as i64 => {
+                      format!("pub const {}: {}c_uint = {};", id, raw, i)
+                  }
+                  MacroValue::Int(i) => format!("pub const {}: {}c_longlong = {};", id, raw, i),
+                  MacroValue::UInt(i) => format!("pub const {}: {}c_ulonglong = {};", id, raw, i),
+                  MacroValue::Float(f) => format!("pub const {}: {}c_double = {:?};", id, raw, f),
+                  MacroValue::Char(c) => {
+                      format!("pub const {id}: {raw}c_char = {}u8 as {raw}c_char;",
+                              c,
+                              id = id,
+                              raw = raw)
+                  }
+                  MacroValue::Str(ref bytes) => {
+                      let escaped: String = bytes.iter()
+                                                 .flat_map(|b| ascii::escape_default(*b))
+                                                 .map(|b| b as char)
+                                                 .collect();
+                      format!("pub const {}: &'static [u8] = b\"{}\\0\";", id, escaped)
+                  }
+              };
+              parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                ctx.ext_cx.cfg(),
+                                                "".to_owned(),
+                                                item_str)
+                  .parse_item()
+                  .unwrap()
+                  .unwrap()
+          })
+          .collect()
+}
+
+/// The Rust names of the generated items starting with `prefix`, paired with
+/// their name without it, leaving out with a warning the short names that are
+/// already taken.
+fn trimmed_names(ctx: &mut GenCtx,
+                 prefix: &str,
+                 types: &[String],
+                 values: &[String],
+                 skipping: &str)
+                 -> Vec<(String, String)> {
+    let names: Vec<_> = types.iter()
+                             .map(|n| (n, true))
+                             .chain(values.iter().map(|n| (n, false)))
+                             .collect();
+    let short_name = |name: &str| {
+        if name.starts_with(prefix) {
+            Some(name[prefix.len()..].to_owned())
+        } else {
+            None
+        }
+    };
+
+    let mut seen = HashSet::new();
+    let mut trimmed = vec![];
+    for &(name, is_type) in &names {
+        let short = match short_name(name) {
+            Some(ref short) if !short.is_empty() &&
+                               !short.starts_with(|c: char| c.is_digit(10)) => short.clone(),
+            _ => continue,
+        };
+        if !seen.insert(name.clone()) {
+            continue;
+        }
+
+        let ambiguous = names.iter().any(|&(other, _)| {
+            other != name && (*other == short || short_name(other).as_ref() == Some(&short))
+        });
+        if ambiguous {
+            let msg = format!("`{}` would be ambiguous, {} `{}`", short, skipping, name);
+            ctx.logger.warn(&msg[..]);
+            continue;
+        }
+
+        trimmed.push(if is_type {
+            (rust_type_id(ctx, name), rust_type_id(ctx, &short))
+        } else {
+            (rust_id(ctx, name).0, rust_id(ctx, &short).0)
+        });
+    }
+    trimmed
+}
+
+/// Re-exports the generated items under their name without `prefix`, unless
+/// the short name is already taken.
+fn mk_prefix_reexports(ctx: &mut GenCtx,
+                       prefix: &str,
+                       types: &[String],
+                       values: &[String])
+                       -> Vec<P<ast::Item>> {
+    trimmed_names(ctx, prefix, types, values, "not re-exporting")
+        .into_iter()
+        .map(|(name, short)| {
+            let use_str = format!("pub use self::{} as {};", name, short);
+            parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                              ctx.ext_cx.cfg(),
+                                              "".to_owned(),
+                                              use_str)
+                .parse_item()
+                .unwrap()
+                .unwrap()
+        })
+        .collect()
+}
+
+/// Renames the generated items to their name without `prefix`, unless the
+/// short name is already taken. The renamed functions and variables keep
+/// linking to their symbol.
+fn trim_prefixes(ctx: &mut GenCtx,
+                 prefix: &str,
+                 types: &[String],
+                 values: &[String],
+                 defs: Vec<P<ast::Item>>)
+                 -> Vec<P<ast::Item>> {
+    let renames = trimmed_names(ctx, prefix, types, values, "not renaming").into_iter().collect();
+    let mut trimmer = PrefixTrimmer {
+        ctx: ctx,
+        renames: renames,
+    };
+    defs.into_iter()
+        .flat_map(|item| trimmer.fold_item(item).into_iter())
+        .collect()
+}
+
+struct PrefixTrimmer<'a, 'r: 'a> {
+    ctx: &'a mut GenCtx<'r>,
+    renames: HashMap<String, String>,
+}
+
+impl<'a, 'r> PrefixTrimmer<'a, 'r> {
+    fn rename(&mut self, i: ast::Ident) -> ast::Ident {
+        match self.renames.get(&*i.name.as_str()) {
+            Some(short) => self.ctx.ext_cx.ident_of(short),
+            None => i,
+        }
+    }
+}
+
+// Only the names of the items and the paths referring to them are renamed, the
+// fields and arguments keep their names.
+impl<'a, 'r> Folder for PrefixTrimmer<'a, 'r> {
+    fn fold_item_simple(&mut self, i: ast::Item) -> ast::Item {
+        let mut i = fold::noop_fold_item_simple(i, self);
+        i.ident = self.rename(i.ident);
+        i
+    }
+
+    fn fold_path(&mut self, p: ast::Path) -> ast::Path {
+        let mut p = fold::noop_fold_path(p, self);
+        for segment in &mut p.segments {
+            segment.identifier = self.rename(segment.identifier);
+        }
+        p
+    }
+
+    fn fold_foreign_item(&mut self, ni: ast::ForeignItem) -> ast::ForeignItem {
+        let symbol = (*ni.ident.name.as_str()).to_owned();
+        let mut ni = fold::noop_fold_foreign_item(ni, self);
+        ni.ident = self.rename(ni.ident);
+        let renamed = *ni.ident.name.as_str() != *symbol;
+        if renamed && !ni.attrs.iter().any(|a| a.check_name("link_name")) {
+            ni.attrs.push(mk_link_name_attr(self.ctx, &symbol));
+        }
+        ni
+    }
+
+    fn fold_mac(&mut self, mac: ast::Mac) -> ast::Mac {
+        fold::noop_fold_mac(mac, self)
+    }
+}
+
+/// Imports the C types and `Option`, so that the bindings can name them
+/// without their path.
+fn mk_prelude(ctx: &mut GenCtx) -> Vec<P<ast::Item>> {
+    let raw = if ctx.std_crate == "core" {
+        "::core::ffi"
+    } else {
+        "::std::os::raw"
+    };
+    let uses = [format!("#[allow(unused_imports)] use ::{}::option::Option;", ctx.std_crate),
+                format!("#[allow(unused_imports)] use {}::*;", raw)];
+
+    uses.iter()
+        .map(|use_str| {
+            parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                              ctx.ext_cx.cfg(),
+                                              "".to_owned(),
+                                              use_str.clone())
+                .parse_item()
+                .unwrap()
+                .unwrap()
+        })
+        .collect()
+}
+
+// Stands for `_Float16`, stored as its bits
+fn mk_float16_struct(ctx: &mut GenCtx) -> P<ast::Item> {
+    let struct_str = r"
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq)]
+        pub struct __BindgenFloat16(pub u16);
+    ";
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      struct_str.to_owned())
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+fn mk_helpers_mod(ctx: &mut GenCtx, name: &str, helpers: Vec<P<ast::Item>>) -> P<ast::Item> {
+    P(ast::Item {
+        ident: ctx.ext_cx.ident_of(name),
+        attrs: vec![],
+        id: ast::DUMMY_NODE_ID,
+        node: ast::ItemKind::Mod(ast::Mod {
+            inner: ctx.span,
+            items: helpers,
+        }),
+        vis: ast::Visibility::Crate(ctx.span),
+        span: ctx.span,
+    })
+}
+
+fn mk_helper_ty(ctx: &mut GenCtx, name: &str) -> ast::Ty {
+    let mut segments = vec![];
+    if let Some(ref module) = ctx.helpers_module {
+        segments.push(module.clone());
+    }
+    segments.push(name.to_owned());
+    mk_ty(ctx, false, segments)
+}
+
+/// Wraps the `extern` blocks of grouped functions in the module `name`, which
+/// sees the types of the bindings.
+fn mk_fn_module(ctx: &mut GenCtx, name: &str, externs: Vec<P<ast::Item>>) -> P<ast::Item> {
+    let use_super = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                      ctx.ext_cx.cfg(),
+                                                      "".to_owned(),
+                                                      "#[allow(unused_imports)] use super::*;"
+                                                          .to_owned())
+                        .parse_item()
+                        .unwrap()
+                        .unwrap();
+    let mut items = vec![use_super];
+    items.extend(externs.into_iter());
+
+    P(ast::Item {
+        ident: ctx.ext_cx.ident_of(name),
+        attrs: vec![],
+        id: ast::DUMMY_NODE_ID,
+        node: ast::ItemKind::Mod(ast::Mod {
+            inner: ctx.span,
+            items: items,
+        }),
+        vis: ast::Visibility::Public,
+        span: ctx.span,
+    })
+}
+
 fn mk_extern(ctx: &mut GenCtx,
              links: &[(String, LinkType)],
              foreign_items: Vec<ast::ForeignItem>,
@@ -402,6 +1041,96 @@ fn mk_extern(ctx: &mut GenCtx,
     })
 }
 
+/// Generates a `DynamicLibrary` struct holding a pointer to each function,
+/// resolved at runtime with `libloading`, instead of an `extern` block.
+fn mk_dynamic_library(ctx: &mut GenCtx, fs: &[Global]) -> Vec<P<ast::Item>> {
+    let mut fields = String::new();
+    let mut inits = String::new();
+    let mut names = String::new();
+    let mut methods = String::new();
+
+    for f in fs {
+        let vi = f.varinfo();
+        let v = vi.borrow();
+        let sig = match v.ty {
+            TFuncPtr(ref sig, _) => sig,
+            _ => unreachable!(),
+        };
+
+        let decl = cfuncty_to_rs(ctx, &*sig.ret_ty, &sig.args[..], sig.is_variadic);
+        let fn_ty = pprust::ty_to_string(&mk_fn_proto_ty(ctx,
+                                                         decl.clone(),
+                                                         ast::Unsafety::Unsafe,
+                                                         sig.abi));
+        let rust_name = foreign_names(ctx, &v.name).0;
+        // The symbol the `link_name` of the extern function would be.
+        let symbol = match v.mangled {
+            Some(ref mangled) => mangled.clone(),
+            None => format!("{}{}", ctx.link_prefix, v.name),
+        };
+
+        fields.push_str(&format!("pub {}: {},\n", rust_name, fn_ty));
+        inits.push_str(&format!("let {} = *try!(library.get::<{}>(b\"{}\\0\"));\n",
+                                rust_name,
+                                fn_ty,
+                                symbol));
+        names.push_str(&format!("{}: {},\n", rust_name, rust_name));
+
+        // Variadic functions can only be called through the field.
+        if decl.variadic {
+            continue;
+        }
+
+        let mut args = vec!["&self".to_owned()];
+        args.extend(decl.inputs.iter().map(pprust::arg_to_string));
+        let arg_names: Vec<_> = decl.inputs.iter().map(|a| pprust::pat_to_string(&a.pat)).collect();
+        let ret = match decl.output {
+            ast::FunctionRetTy::Ty(ref ty) => format!(" -> {}", pprust::ty_to_string(ty)),
+            _ => "".to_owned(),
+        };
+        methods.push_str(&format!("pub unsafe fn {}({}){} {{ (self.{})({}) }}\n",
+                                  rust_name,
+                                  args.join(", "),
+                                  ret,
+                                  rust_name,
+                                  arg_names.join(", ")));
+    }
+
+    let items_str = format!(r"
+        pub struct DynamicLibrary {{
+            __library: ::libloading::Library,
+            {}
+        }}
+        impl DynamicLibrary {{
+            pub unsafe fn new<P: AsRef<::{std}::ffi::OsStr>>(path: P)
+                                                          -> ::{std}::io::Result<DynamicLibrary> {{
+                let library = try!(::libloading::Library::new(path));
+                {}
+                Ok(DynamicLibrary {{
+                    {}
+                    __library: library,
+                }})
+            }}
+            {}
+        }}
+    ",
+                            fields,
+                            inits,
+                            names,
+                            methods,
+                            std = ctx.std_crate);
+
+    let mut parser = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                       ctx.ext_cx.cfg(),
+                                                       "".to_owned(),
+                                                       items_str);
+    let mut items = vec![];
+    while let Some(item) = parser.parse_item().unwrap() {
+        items.push(item);
+    }
+    items
+}
+
 fn remove_redundant_decl(gs: Vec<Global>) -> Vec<Global> {
     fn check_decl(a: &Global, ty: &Type) -> bool {
         match *a {
@@ -435,6 +1164,152 @@ fn remove_redundant_decl(gs: Vec<Global>) -> Vec<Global> {
       .collect()
 }
 
+fn global_name(g: &Global) -> Option<String> {
+    match *g {
+        GType(ref ti) => Some(ti.borrow().name.clone()),
+        GComp(ref ci) |
+        GCompDecl(ref ci) => Some(ci.borrow().name.clone()),
+        GEnum(ref ei) |
+        GEnumDecl(ref ei) => Some(ei.borrow().name.clone()),
+        GVar(ref vi) |
+        GFunc(ref vi) => Some(vi.borrow().name.clone()),
+        GAlias(ref name, _) |
+        GMacro(ref name, _) => Some(name.clone()),
+        GOther => None,
+    }
+}
+
+/// Whether `ty` names one of `names`, looking through the pointers, arrays and
+/// function signatures.
+fn refers_to_any(ty: &Type, names: &[String]) -> bool {
+    match *ty {
+        TPtr(ref t, _, _) |
+        TArray(ref t, _, _) => refers_to_any(t, names),
+        TFuncPtr(ref sig, _) |
+        TFuncProto(ref sig, _) => {
+            refers_to_any(&sig.ret_ty, names) ||
+            sig.args.iter().any(|&(_, ref t)| refers_to_any(t, names))
+        }
+        TNamed(ref ti) => names.contains(&ti.borrow().name),
+        TComp(ref ci) => names.contains(&ci.borrow().name),
+        TEnum(ref ei) => names.contains(&ei.borrow().name),
+        _ => false,
+    }
+}
+
+/// Whether the kind of the global is generated with the options.
+fn emits_kind(options: &BindgenOptions, g: &Global) -> bool {
+    match *g {
+        GFunc(_) => options.functions,
+        GEnum(_) | GEnumDecl(_) => options.enums,
+        GVar(_) => options.globals,
+        GType(_) | GComp(_) | GCompDecl(_) => options.types,
+        _ => true,
+    }
+}
+
+/// The files of the globals which are generated, without duplicates. The
+/// files are given in the order of the globals.
+pub fn emitted_files(options: &BindgenOptions,
+                     patterns: &NamePatterns,
+                     globs: &[Global],
+                     files: &[String])
+                     -> Vec<String> {
+    let mut emitted: Vec<String> = vec![];
+    for (g, file) in globs.iter().zip(files) {
+        let blocklisted = global_name(g).map_or(false, |n| matches(&patterns.blocklist, &n));
+        if file.is_empty() || blocklisted || !emits_kind(options, g) || emitted.contains(file) {
+            continue;
+        }
+        emitted.push(file.clone());
+    }
+    emitted
+}
+
+/// Drops the items matching the blocklist, whatever their kind. The
+/// blocklisted types the remaining items still refer to are kept as opaque
+/// blobs, with a warning.
+fn remove_blocklisted(ctx: &mut GenCtx, gs: Vec<Global>) -> Vec<Global> {
+    let blocklisted = |ctx: &GenCtx, g: &Global| {
+        global_name(g).map_or(false, |n| matches(&ctx.patterns.blocklist, &n))
+    };
+    let blocked_types: Vec<String> = gs.iter()
+                                       .filter(|g| {
+                                           match **g {
+                                               GType(_) | GComp(_) | GCompDecl(_) |
+                                               GEnum(_) | GEnumDecl(_) => blocklisted(ctx, g),
+                                               _ => false,
+                                           }
+                                       })
+                                       .filter_map(global_name)
+                                       .collect();
+
+    let mut referenced = HashSet::new();
+    for g in gs.iter().filter(|g| !blocklisted(ctx, g)) {
+        for name in &blocked_types {
+            let names = [name.clone()];
+            let refers = match *g {
+                GType(ref ti) => refers_to_any(&ti.borrow().ty, &names),
+                GComp(ref ci) => {
+                    ci.borrow().members.iter().any(|m| {
+                        match *m {
+                            CompMember::Field(ref f) |
+                            CompMember::CompField(_, ref f) |
+                            CompMember::EnumField(_, ref f) => refers_to_any(&f.ty, &names),
+                            _ => false,
+                        }
+                    })
+                }
+                GVar(ref vi) |
+                GFunc(ref vi) => refers_to_any(&vi.borrow().ty, &names),
+                _ => false,
+            };
+            if refers {
+                let msg = format!("`{}` refers to the blocklisted `{}`, keeping it opaque",
+                                  global_name(g).unwrap(),
+                                  name);
+                ctx.logger.warn(&msg[..]);
+                referenced.insert(name.clone());
+            }
+        }
+    }
+
+    gs.into_iter()
+      .filter_map(|g| {
+          if !blocklisted(ctx, &g) {
+              return Some(g);
+          }
+          let name = global_name(&g).unwrap();
+          match g {
+              GComp(ci) => {
+                  if !referenced.contains(&name) {
+                      return None;
+                  }
+                  ci.borrow_mut().opaque = true;
+                  Some(GComp(ci))
+              }
+              GType(ref ti) if referenced.contains(&name) => {
+                  let layout = ti.borrow().layout;
+                  Some(mk_opaque_comp(name, layout))
+              }
+              GEnum(ref ei) if referenced.contains(&name) => {
+                  let layout = ei.borrow().layout;
+                  Some(mk_opaque_comp(name, layout))
+              }
+              GCompDecl(_) | GEnumDecl(_) if referenced.contains(&name) => Some(g),
+              _ => None,
+          }
+      })
+      .collect()
+}
+
+/// An opaque struct standing for a type, with its layout.
+fn mk_opaque_comp(name: String, layout: Layout) -> Global {
+    let mut ci = CompInfo::new(name, CompKind::Struct, vec![], layout);
+    ci.opaque = true;
+    GComp(Rc::new(RefCell::new(ci)))
+}
+
 fn tag_dup_decl(gs: &[Global]) -> Vec<Global> {
     fn check(name1: &str, name2: &str) -> bool {
         !name1.is_empty() && name1 == name2
@@ -521,7 +1396,7 @@ fn ctypedef_to_rs(ctx: &mut GenCtx,
 
         P(ast::Item {
             ident: ctx.ext_cx.ident_of(&rust_name),
-            attrs: Vec::new(),
+            attrs: original_name_attrs(ctx, name, &rust_name),
             id: ast::DUMMY_NODE_ID,
             node: base,
             vis: ast::Visibility::Public,
@@ -532,7 +1407,11 @@ fn ctypedef_to_rs(ctx: &mut GenCtx,
     match *ty {
         TComp(ref ci) => {
             let is_empty = ci.borrow().name.is_empty();
-            if is_empty {
+            let size_unknown = ci.borrow().opaque && ci.borrow().layout.size == 0;
+            if is_empty && size_unknown {
+                // Without a size, it can only be used by pointer.
+                vec![opaque_to_rs(ctx, name)]
+            } else if is_empty {
                 ci.borrow_mut().name = name.into();
                 let c = ci.borrow().clone();
                 comp_to_rs(ctx,
@@ -541,6 +1420,7 @@ fn ctypedef_to_rs(ctx: &mut GenCtx,
                            options,
                            derive_debug,
                            c.layout,
+                           c.opaque,
                            c.members)
             } else {
                 vec![mk_item(ctx, name, ty)]
@@ -556,10 +1436,92 @@ fn ctypedef_to_rs(ctx: &mut GenCtx,
                 vec![mk_item(ctx, name, ty)]
             }
         }
+        TArray(ref elem, len, _) if options.array_newtype_indexing => {
+            mk_array_newtype(ctx, options, derive_debug, name, ty, elem, len)
+        }
         _ => vec![mk_item(ctx, name, ty)],
     }
 }
 
+/// Generates an array typedef as a newtype over the array, indexable like the
+/// array itself.
+fn mk_array_newtype(ctx: &mut GenCtx,
+                    options: &BindgenOptions,
+                    derive_debug: bool,
+                    name: &str,
+                    ty: &Type,
+                    elem: &Type,
+                    len: usize)
+                    -> Vec<P<ast::Item>> {
+    let rust_name = rust_type_id(ctx, name);
+    let elem_ty = pprust::ty_to_string(&cty_to_rs(ctx, elem));
+    let struct_str = format!("
+        #[repr(C)]
+        pub struct {}(pub [{}; {}usize]);
+    ",
+                             rust_name,
+                             elem_ty,
+                             len);
+    let mut newtype = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                        ctx.ext_cx.cfg(),
+                                                        "".to_owned(),
+                                                        struct_str)
+                          .parse_item()
+                          .unwrap()
+                          .unwrap();
+    let can_copy = ty.can_derive_copy(&options.no_copy);
+    let can_derive = ty.can_auto_derive();
+    let mut attrs = original_name_attrs(ctx, name, &rust_name);
+    match (can_copy, can_derive) {
+        (true, true) => attrs.push(mk_deriving_copy_clone_attr(ctx)),
+        (true, false) => attrs.push(mk_attr(ctx, "derive", &["Copy"])),
+        _ => {}
+    }
+    if derive_debug && can_derive {
+        attrs.push(mk_deriving_debug_attr(ctx));
+    }
+    newtype = newtype.map(|mut item| {
+        item.attrs.extend(attrs.into_iter());
+        item
+    });
+
+    let index_str = format!("
+        impl ::{std}::ops::Index<usize> for {name} {{
+            type Output = {elem};
+            {track_caller}
+            fn index(&self, index: usize) -> &{elem} {{ &self.0[index] }}
+        }}
+    ",
+                            std = ctx.std_crate,
+                            name = rust_name,
+                            elem = elem_ty,
+                            track_caller = track_caller_attr(ctx));
+    let index_mut_str = format!("
+        impl ::{std}::ops::IndexMut<usize> for {name} {{
+            {track_caller}
+            fn index_mut(&mut self, index: usize) -> &mut {elem} {{ &mut self.0[index] }}
+        }}
+    ",
+                                std = ctx.std_crate,
+                                name = rust_name,
+                                elem = elem_ty,
+                                track_caller = track_caller_attr(ctx));
+    let mut items = vec![newtype];
+    if can_copy && !can_derive {
+        items.push(mk_clone_impl(ctx, &rust_name));
+    }
+    for impl_str in vec![index_str, index_mut_str] {
+        items.push(parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                     ctx.ext_cx.cfg(),
+                                                     "".to_owned(),
+                                                     impl_str)
+                       .parse_item()
+                       .unwrap()
+                       .unwrap());
+    }
+    items
+}
+
 /// Converts a C composed type (struct or union) to Rust AST Items.
 fn comp_to_rs(ctx: &mut GenCtx,
               kind: CompKind,
@@ -567,8 +1529,12 @@ fn comp_to_rs(ctx: &mut GenCtx,
               options: &BindgenOptions,
               derive_debug: bool,
               layout: Layout,
+              opaque: bool,
               members: Vec<CompMember>)
               -> Vec<P<ast::Item>> {
+    if opaque || matches(&ctx.patterns.opaque_types, &name) {
+        return opaque_blob_to_rs(ctx, &name, derive_debug, layout);
+    }
     match kind {
         CompKind::Struct => cstruct_to_rs(ctx, &name, options, derive_debug, layout, members),
         CompKind::Union => cunion_to_rs(ctx, name, options, derive_debug, layout, members),
@@ -629,6 +1595,36 @@ fn gen_padding_fields(ctx: &mut GenCtx,
           .collect()
 }
 
+/// Pads with byte arrays, which leave the alignment of the struct unchanged.
+fn gen_byte_padding_fields(ctx: &mut GenCtx,
+                           idx: usize,
+                           padding_size: usize)
+                           -> Vec<ast::StructField> {
+    const MAX_ARRAY_CLONE_LEN: usize = 32; // impl<T: Copy> Clone for [T; 32]
+
+    let u8_ty = P(mk_ty(ctx, false, vec!["u8".to_owned()]));
+    let mut lens = vec![MAX_ARRAY_CLONE_LEN; padding_size / MAX_ARRAY_CLONE_LEN];
+    if padding_size % MAX_ARRAY_CLONE_LEN > 0 {
+        lens.push(padding_size % MAX_ARRAY_CLONE_LEN);
+    }
+
+    lens.into_iter()
+        .enumerate()
+        .map(|(i, len)| {
+            let name = format!("_bindgen_padding_{}_", idx + i);
+
+            ast::StructField {
+                span: ctx.span,
+                vis: ast::Visibility::Inherited,
+                ident: Some(ctx.ext_cx.ident_of(&name[..])),
+                id: ast::DUMMY_NODE_ID,
+                ty: P(mk_arrty(ctx, &u8_ty, len)),
+                attrs: Vec::new(),
+            }
+        })
+        .collect()
+}
+
 /// Converts a C struct to Rust AST Items.
 fn cstruct_to_rs(ctx: &mut GenCtx,
                  name: &str,
@@ -651,6 +1647,17 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
     // Waiting for https://github.com/rust-lang/rfcs/issues/1038
     let mut can_derive_debug = derive_debug;
     let mut can_derive_clone = true;
+    let mut can_derive_copy = !options.no_copy.iter().any(|n| n == name);
+    let mut can_derive_partial_eq = true;
+    let mut can_derive_eq = true;
+    let mut can_derive_default = true;
+    let mut field_offsets = vec![];
+    let mut base_ty = None;
+    let mut hash_fields = vec![];
+    // The blobs of the anonymous members past 32 elements are shown as slices
+    // by a manual `Debug` implementation.
+    let mut debug_fields = vec![];
+    let mut manual_debug = false;
 
     for m in &members {
         debug!("convert field {} {:?}", m.name(), m);
@@ -663,8 +1670,26 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
             CompMember::EnumField(ref rc_e, ref f) => (None, Some(rc_e), Some(f)),
         };
 
-        if !layout.packed && m.layout().align != 0 && (offset % m.layout().align) != 0 {
-            let padding_size = m.layout().align - (offset % m.layout().align);
+        let align = m.layout().align;
+        let natural_offset = if !layout.packed && align != 0 && (offset % align) != 0 {
+            offset + align - (offset % align)
+        } else {
+            offset
+        };
+        let field_offset = opt_f.and_then(|f| f.offset).unwrap_or(0);
+
+        if field_offset > natural_offset {
+            // Attributes like `aligned` can move a field past its natural
+            // offset, pad up to the offset reported by clang.
+            let mut padding_fields = gen_byte_padding_fields(ctx, paddings, field_offset - offset);
+
+            fields.append(&mut padding_fields);
+
+            paddings += padding_fields.len();
+
+            offset = field_offset;
+        } else if natural_offset != offset {
+            let padding_size = natural_offset - offset;
 
             if padding_size > mem::size_of::<u64>() {
                 let mut padding_fields = gen_padding_fields(ctx, paddings, offset, padding_size);
@@ -696,16 +1721,82 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
                 can_derive_debug = false;
                 can_derive_clone = false;
             }
+            if !f.ty.can_derive_copy(&options.no_copy) {
+                can_derive_copy = false;
+            }
+            let holds_opaque = holds_opaque_type(ctx, &f.ty);
+            can_derive_partial_eq &= !holds_opaque && f.ty.can_derive_eq(true);
+            can_derive_eq &= !holds_opaque && f.ty.can_derive_eq(false);
+            can_derive_default &= f.ty.can_derive_default();
+
+            if let Some(field_offset) = f.offset {
+                field_offsets.push((f_name.clone(), field_offset));
+            }
 
             let f_ty = P(cty_to_rs(ctx, &f.ty));
+            if f.is_base {
+                base_ty = Some(f_ty.clone());
+            }
+            // A zeroed Rust enum without a zero variant is undefined behavior
+            let maybe_uninit = options.maybe_uninit_fields && f.bitfields.is_none() &&
+                               !zeroed_is_valid(ctx, options, &f.ty);
+            let f_ty = if maybe_uninit {
+                methods.extend(mk_maybe_uninit_accessors(ctx, &f_name, &f_ty).into_iter());
+                P(mk_maybe_uninit_ty(ctx, f_ty))
+            } else {
+                f_ty
+            };
+
+            let f_attrs = match f.bitfields {
+                Some(_) => vec![],
+                None => {
+                    let mut attrs = doc_attrs(ctx, &f.comment);
+                    attrs.extend(original_name_attrs(ctx, &f.name, &f_name).into_iter());
+                    attrs
+                }
+            };
+            let f_vis = match f.bitfields {
+                Some(_) => Visibility::Public,
+                None => field_visibility(options, name, &f.name),
+            };
+            if f_vis != Visibility::Public && !maybe_uninit {
+                methods.extend(mk_field_getter(ctx, &f_name, &f_ty).into_iter());
+            }
+            if options.volatile_accessors && f.is_volatile && f.bitfields.is_none() {
+                methods.extend(mk_volatile_field_accessors(ctx, &f_name, &f_ty).into_iter());
+            }
+            if f.bitfields.is_none() && is_cstr_field(ctx, name, &f.name) {
+                if let TArray(ref elem_ty, len, _) = f.ty {
+                    let is_char = match **elem_ty {
+                        TInt(ISChar, _) | TInt(IUChar, _) => true,
+                        _ => false,
+                    };
+                    if is_char && len > 0 {
+                        let elem_ty = cty_to_rs(ctx, elem_ty);
+                        methods.extend(mk_cstr_accessors(ctx, &f_name, len, &elem_ty).into_iter());
+                    }
+                }
+            }
+
+            let hash = if maybe_uninit || holds_opaque {
+                None
+            } else {
+                field_hash(&f.ty)
+            };
+            hash_fields.push((f_name.clone(), hash));
+            debug_fields.push((f_name.clone(), false));
 
             fields.push(ast::StructField {
                 span: ctx.span,
-                vis: ast::Visibility::Public,
+                vis: match f_vis {
+                    Visibility::Public => ast::Visibility::Public,
+                    Visibility::PublicCrate => ast::Visibility::Crate(ctx.span),
+                    Visibility::Private => ast::Visibility::Inherited,
+                },
                 ident: Some(ctx.ext_cx.ident_of(&f_name[..])),
                 id: ast::DUMMY_NODE_ID,
                 ty: f_ty,
-                attrs: Vec::new(),
+                attrs: f_attrs,
             });
         }
 
@@ -713,12 +1804,26 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
             let c = rc_c.borrow();
             if c.name.is_empty() {
                 unnamed += 1;
-                let field_name = format!("_bindgen_data_{}_", unnamed);
+                let field_name = format!("{}{}_", options.anon_field_prefix, unnamed);
+                // Like the arrays, the blob can't derive past 32 elements.
+                let large_blob = blob_len(c.layout) > 32;
+                if large_blob {
+                    manual_debug = true;
+                    can_derive_clone = false;
+                }
+                let hash = if large_blob {
+                    None
+                } else {
+                    Some(FieldHash::Plain)
+                };
+                hash_fields.push((field_name.clone(), hash));
+                debug_fields.push((field_name.clone(), large_blob));
                 fields.push(mk_blob_field(ctx, &field_name, c.layout, ctx.span));
                 methods.extend(gen_comp_methods(ctx,
                                                 &field_name,
                                                 0,
                                                 c.kind,
+                                                c.layout.packed,
                                                 &c.members,
                                                 &mut extra,
                                                 options,
@@ -731,6 +1836,7 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
                                         options,
                                         derive_debug,
                                         c.layout,
+                                        c.opaque,
                                         c.members.clone())
                                  .into_iter());
             }
@@ -751,8 +1857,37 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
         offset += m.layout().size as usize;
     }
 
+    for &(_, ref ptr_field, ref len_field) in
+        options.slice_accessors.iter().filter(|&&(ref s, _, _)| s == name) {
+        let find = |field_name: &str| {
+            members.iter().filter_map(|m| {
+                match *m {
+                    CompMember::Field(ref f) if f.name == field_name => Some(f.ty.clone()),
+                    _ => None,
+                }
+            }).next()
+        };
+        match (find(ptr_field), find(len_field)) {
+            (Some(TPtr(elem_ty, _, _)), Some(_)) => {
+                let elem_ty = cty_to_rs(ctx, &elem_ty);
+                let ptr_name = rust_id(ctx, ptr_field).0;
+                let len_name = rust_id(ctx, len_field).0;
+                methods.extend(mk_slice_accessor(ctx, &ptr_name, &len_name, &elem_ty).into_iter());
+            }
+            _ => {
+                let msg = format!("`{}` has no `{}` pointer and `{}` length fields, not \
+                                   generating the slice accessor",
+                                  name,
+                                  ptr_field,
+                                  len_field);
+                ctx.logger.warn(&msg[..]);
+            }
+        }
+    }
+
     if offset < layout.size {
-        let mut padding_fields = gen_padding_fields(ctx, paddings, offset, layout.size - offset);
+        // The trailing bytes may not start at a `u64` boundary.
+        let mut padding_fields = gen_byte_padding_fields(ctx, paddings, layout.size - offset);
 
         fields.append(&mut padding_fields);
     }
@@ -761,15 +1896,40 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
                                     ast::Generics::default());
 
     let id = rust_type_id(ctx, name);
-    let mut attrs = vec![mk_repr_attr(ctx, layout)];
-    if can_derive_clone {
-        attrs.push(mk_attr(ctx, "derive", &["Copy", "Clone"]));
-    } else {
-        attrs.push(mk_attr(ctx, "derive", &["Copy"]));
+    let mut attrs = original_name_attrs(ctx, name, &id);
+    attrs.push(mk_repr_attr(ctx, layout));
+    match (can_derive_copy, can_derive_clone) {
+        (true, true) => attrs.push(mk_attr(ctx, "derive", &["Copy", "Clone"])),
+        (true, false) => attrs.push(mk_attr(ctx, "derive", &["Copy"])),
+        (false, true) => attrs.push(mk_attr(ctx, "derive", &["Clone"])),
+        (false, false) => {}
     }
-    if can_derive_debug {
+    let manual_debug = can_derive_debug && manual_debug;
+    if can_derive_debug && !manual_debug {
         attrs.push(mk_deriving_debug_attr(ctx));
     }
+    // The floats are hashed by their bits with `manual_hash_floats`
+    let manual_hash = options.manual_hash_floats && !(can_derive_clone && can_derive_eq) &&
+                      hash_fields.iter().all(|&(_, ref hash)| hash.is_some());
+    // The arrays and blobs past 32 elements can't derive anything but `Copy`
+    let mut derives = extra_derives(ctx,
+                                    name,
+                                    can_derive_clone && can_derive_partial_eq,
+                                    can_derive_clone && can_derive_eq,
+                                    (can_derive_clone && can_derive_eq) || manual_hash,
+                                    can_derive_clone && can_derive_default);
+    let manual_hash = manual_hash && derives.contains(&"Hash");
+    if manual_hash {
+        derives.retain(|d| *d != "Hash");
+    }
+    // `Default` calls the initializer associated with the struct, if any
+    let initializer = ctx.initializers.get(name).cloned();
+    if initializer.is_some() {
+        derives.retain(|d| *d != "Default");
+    }
+    if !derives.is_empty() {
+        attrs.push(mk_attr(ctx, "derive", &derives));
+    }
     let struct_def = P(ast::Item {
         ident: ctx.ext_cx.ident_of(&id),
         attrs: attrs,
@@ -779,13 +1939,18 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
         span: ctx.span,
     });
 
+    if options.emit_layout_constants {
+        let consts = mk_layout_consts(ctx, layout);
+        methods = consts.into_iter().chain(methods.into_iter()).collect();
+    }
+
     let mut items = vec![struct_def];
     if !methods.is_empty() {
         let impl_ = ast::ItemKind::Impl(ast::Unsafety::Normal,
                                         ast::ImplPolarity::Positive,
                                         ast::Generics::default(),
                                         None,
-                                        P(mk_ty(ctx, false, vec![id])),
+                                        P(mk_ty(ctx, false, vec![id.clone()])),
                                         methods);
         items.push(P(ast::Item {
             ident: ctx.ext_cx.ident_of(name),
@@ -798,22 +1963,580 @@ fn cstruct_to_rs(ctx: &mut GenCtx,
     }
 
     if !can_derive_clone {
-        items.push(mk_clone_impl(ctx, name));
+        if can_derive_copy {
+            items.push(mk_clone_impl(ctx, &id));
+        } else {
+            items.push(mk_bitwise_clone_impl(ctx, &id));
+        }
+    }
+
+    if manual_debug {
+        items.push(mk_struct_debug_impl(ctx, &id, &debug_fields));
+    }
+
+    if let Some(ref init) = initializer {
+        items.push(mk_initializer_default_impl(ctx, &id, init));
+    } else if !derives.contains(&"Default") {
+        items.push(mk_default_impl(ctx, &id));
+    }
+
+    if manual_hash {
+        items.push(mk_float_hash_impl(ctx, &id, &hash_fields));
+    }
+
+    if let Some(base_ty) = base_ty {
+        if options.emit_base_deref {
+            items.extend(mk_base_deref_impls(ctx, &id, &base_ty).into_iter());
+        }
+    }
+
+    if options.layout_offset_tests {
+        // Without `#[repr(align)]`, the alignment of the over-aligned structs
+        // can't be reproduced.
+        let members_align = members.iter().map(|m| m.layout().align).max().unwrap_or(1);
+        let checked_align = if layout.align > members_align {
+            None
+        } else {
+            Some(layout.align)
+        };
+        items.push(mk_layout_test(ctx, &id, layout.size, checked_align, &field_offsets));
+    }
+
+    if options.pod_byte_helpers.iter().any(|n| n == name) {
+        if members.iter().any(|m| m.has_pointers()) {
+            let msg = format!("`{}` contains pointers, not generating byte helpers", name);
+            ctx.logger.warn(&msg[..]);
+        } else {
+            items.push(mk_pod_byte_helpers_impl(ctx, &id, layout.size));
+        }
+    }
+
+    items.extend(extra.into_iter());
+    items
+}
+
+/// Picks the traits asked with `Builder::derive` that the struct `name` can
+/// derive, warning about the others.
+fn extra_derives(ctx: &mut GenCtx,
+                 name: &str,
+                 partial_eq: bool,
+                 eq: bool,
+                 hash: bool,
+                 default: bool)
+                 -> Vec<&'static str> {
+    let mut derives = vec![];
+    for (&trait_name, &can_derive) in DERIVABLE_TRAITS.iter().zip(&[partial_eq, eq, hash, default]) {
+        if !matches(&ctx.patterns.derives, trait_name) {
+            continue;
+        }
+        if can_derive {
+            derives.push(trait_name);
+        } else {
+            let msg = format!("`{}` has a field that can't derive `{}`, not deriving it",
+                              name,
+                              trait_name);
+            ctx.logger.warn(&msg[..]);
+        }
+    }
+    derives
+}
+
+/// Whether the type is or holds a composite matching the opaque types, which
+/// is emitted as a blob without the comparison traits.
+fn holds_opaque_type(ctx: &GenCtx, ty: &Type) -> bool {
+    match *ty {
+        TArray(ref t, _, _) => holds_opaque_type(ctx, t),
+        TNamed(ref ti) => holds_opaque_type(ctx, &ti.borrow().ty),
+        TComp(ref comp) => {
+            let c = comp.borrow();
+            matches(&ctx.patterns.opaque_types, &comp_name(c.kind, &c.name)) ||
+            c.members.iter().any(|m| {
+                match *m {
+                    CompMember::Field(ref f) |
+                    CompMember::CompField(_, ref f) => holds_opaque_type(ctx, &f.ty),
+                    _ => false,
+                }
+            })
+        }
+        _ => false,
+    }
+}
+
+/// How a struct field is hashed by the manual `Hash` implementations.
+enum FieldHash {
+    Plain,
+    /// A float, hashed through its bits.
+    FloatBits,
+    /// An array of floats.
+    FloatArrayBits,
+}
+
+fn is_float(ty: &Type) -> bool {
+    match *ty {
+        TFloat(..) => true,
+        TNamed(ref ti) => is_float(&ti.borrow().ty),
+        _ => false,
     }
+}
+
+/// How the field of type `ty` can be hashed, `None` if it can't.
+fn field_hash(ty: &Type) -> Option<FieldHash> {
+    match *ty {
+        _ if is_float(ty) => Some(FieldHash::FloatBits),
+        TArray(ref t, size, _) if size <= 32 && is_float(t) => Some(FieldHash::FloatArrayBits),
+        _ if ty.can_derive_eq(false) => Some(FieldHash::Plain),
+        _ => None,
+    }
+}
+
+/// Implements `Hash` for the struct `ty_name`, hashing the float fields by
+/// their bit patterns. `-0.0` is hashed as `0.0`, as they compare equal.
+fn mk_float_hash_impl(ctx: &GenCtx,
+                      ty_name: &str,
+                      fields: &[(String, Option<FieldHash>)])
+                      -> P<ast::Item> {
+    let stmts: Vec<String> = fields.iter()
+                                   .map(|&(ref name, ref hash)| {
+                                       match *hash {
+                                           Some(FieldHash::FloatBits) => {
+                                               format!("(if self.{0} == 0.0 {{ 0.0 }} else \
+                                                        {{ self.{0} }}).to_bits().hash(state);",
+                                                       name)
+                                           }
+                                           Some(FieldHash::FloatArrayBits) => {
+                                               format!("for &x in self.{}.iter() {{ \
+                                                        (if x == 0.0 {{ 0.0 }} else {{ x }})\
+                                                        .to_bits().hash(state); }}",
+                                                       name)
+                                           }
+                                           _ => format!("self.{}.hash(state);", name),
+                                       }
+                                   })
+                                   .collect();
+    let impl_str = format!(r"
+        impl ::{std}::hash::Hash for {} {{
+            fn hash<H: ::{std}::hash::Hasher>(&self, state: &mut H) {{
+                use ::{std}::hash::Hash;
+                {}
+            }}
+        }}
+    ",
+                           ty_name,
+                           stmts.join(" "),
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+/// Derefs the class `ty_name` to its C++ base class, stored in `_base`.
+fn mk_base_deref_impls(ctx: &GenCtx, ty_name: &str, base_ty: &ast::Ty) -> Vec<P<ast::Item>> {
+    let base_ty = pprust::ty_to_string(base_ty);
+    let impls = [format!(r"
+        impl ::{std}::ops::Deref for {name} {{
+            type Target = {base};
+            fn deref(&self) -> &{base} {{ &self._base }}
+        }}
+    ",
+                         name = ty_name,
+                         base = base_ty,
+                         std = ctx.std_crate),
+                 format!(r"
+        impl ::{std}::ops::DerefMut for {name} {{
+            fn deref_mut(&mut self) -> &mut {base} {{ &mut self._base }}
+        }}
+    ",
+                         name = ty_name,
+                         base = base_ty,
+                         std = ctx.std_crate)];
+
+    impls.iter()
+         .map(|impl_str| {
+             parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                               ctx.ext_cx.cfg(),
+                                               "".to_owned(),
+                                               impl_str.clone())
+                 .parse_item()
+                 .unwrap()
+                 .unwrap()
+         })
+         .collect()
+}
+
+// Implements std::clone::Clone using dereferencing
+fn mk_clone_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
+    let impl_str = format!(r"
+        impl ::{std}::clone::Clone for {} {{
+            fn clone(&self) -> Self {{ *self }}
+        }}
+    ",
+                           ty_name,
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+fn field_visibility(options: &BindgenOptions, struct_name: &str, field_name: &str) -> Visibility {
+    let field_path = format!("{}::{}", struct_name, field_name);
+    let find = |name: &str| {
+        options.field_visibility_overrides
+               .iter()
+               .rev()
+               .find(|&&(ref n, _)| n == name)
+               .map(|&(_, vis)| vis)
+    };
+    find(&field_path).or_else(|| find(struct_name)).unwrap_or(options.field_visibility)
+}
+
+// Gives read access to a field which is not `pub`
+fn mk_field_getter(ctx: &GenCtx, f_name: &str, f_ty: &ast::Ty) -> Option<ast::ImplItem> {
+    let impl_str = format!(r"
+        impl X {{
+            pub fn get_{}(&self) -> &{} {{
+                &self.{}
+            }}
+        }}
+    ",
+                           f_name,
+                           pprust::ty_to_string(f_ty),
+                           f_name);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.last().cloned(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+/// Whether all zeroes is a valid value of the type, which it isn't for the Rust
+/// enums without a zero variant.
+fn zeroed_is_valid(ctx: &GenCtx, options: &BindgenOptions, ty: &Type) -> bool {
+    match *ty {
+        TNamed(ref ti) => zeroed_is_valid(ctx, options, &ti.borrow().ty),
+        TArray(ref t, _, _) => zeroed_is_valid(ctx, options, t),
+        TEnum(ref ei) => {
+            let e = ei.borrow();
+            let name = enum_name(&e.name);
+            let as_integer = !options.rust_enums ||
+                             options.constified_enum_newtype.iter().any(|n| *n == name) ||
+                             matches(&ctx.patterns.bitfield_enums, &name) ||
+                             options.enum_with_unknown.iter().any(|n| *n == name);
+            as_integer || e.items.iter().any(|item| item.val == 0)
+        }
+        _ => true,
+    }
+}
+
+fn mk_maybe_uninit_ty(ctx: &GenCtx, ty: P<ast::Ty>) -> ast::Ty {
+    let idents = [ctx.std_crate, "mem", "MaybeUninit"]
+                     .iter()
+                     .map(|item| ctx.ext_cx.ident_of(item))
+                     .collect();
+    ctx.ext_cx
+       .ty_path(ctx.ext_cx.path_all(ctx.span, true, idents, Vec::new(), vec![ty], Vec::new()))
+       .unwrap()
+}
+
+// Reads and writes a field wrapped in `MaybeUninit`, which has to be written
+// before being read
+fn mk_maybe_uninit_accessors(ctx: &GenCtx, f_name: &str, f_ty: &ast::Ty) -> Vec<ast::ImplItem> {
+    let impl_str = format!(r"
+        impl X {{
+            pub unsafe fn get_{name}(&self) -> {ty} {{
+                self.{name}.assume_init()
+            }}
+            pub fn set_{name}(&mut self, value: {ty}) {{
+                self.{name} = ::{std}::mem::MaybeUninit::new(value);
+            }}
+        }}
+    ",
+                           name = f_name,
+                           ty = pprust::ty_to_string(f_ty),
+                           std = ctx.std_crate);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.clone(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+// The size and alignment reported by clang, as associated constants
+fn mk_layout_consts(ctx: &GenCtx, layout: Layout) -> Vec<ast::ImplItem> {
+    let impl_str = format!(r"
+        impl X {{
+            pub const SIZE: usize = {};
+            pub const ALIGN: usize = {};
+        }}
+    ",
+                           layout.size,
+                           layout.align);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.clone(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+// Reads and writes a volatile field without letting the accesses be elided
+fn mk_volatile_field_accessors(ctx: &GenCtx, f_name: &str, f_ty: &ast::Ty) -> Vec<ast::ImplItem> {
+    let impl_str = format!(r"
+        impl X {{
+            pub fn read_{name}(&self) -> {ty} {{
+                unsafe {{ ::{std}::ptr::read_volatile(&self.{name}) }}
+            }}
+            pub fn write_{name}(&mut self, value: {ty}) {{
+                unsafe {{ ::{std}::ptr::write_volatile(&mut self.{name}, value) }}
+            }}
+        }}
+    ",
+                           name = f_name,
+                           ty = pprust::ty_to_string(f_ty),
+                           std = ctx.std_crate);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.clone(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+/// Whether the `char` array `field` of the struct `struct_name` holds a C
+/// string, asked with `Struct` or `Struct::field`.
+fn is_cstr_field(ctx: &GenCtx, struct_name: &str, field: &str) -> bool {
+    let field_path = format!("{}::{}", struct_name, field);
+    matches(&ctx.patterns.cstr_fields, struct_name) ||
+    matches(&ctx.patterns.cstr_fields, &field_path)
+}
+
+// Views a `char` array field as a `CStr`, and copies a `CStr` into it,
+// truncated to leave room for the nul
+fn mk_cstr_accessors(ctx: &GenCtx,
+                     f_name: &str,
+                     len: usize,
+                     elem_ty: &ast::Ty)
+                     -> Vec<ast::ImplItem> {
+    let impl_str = format!(r#"
+        impl X {{
+            {track_caller}
+            pub fn {name}_cstr(&self) -> &::{std}::ffi::CStr {{
+                let bytes = unsafe {{
+                    ::{std}::slice::from_raw_parts(self.{name}.as_ptr() as *const u8, {len})
+                }};
+                match bytes.iter().position(|&b| b == 0) {{
+                    Some(nul) => unsafe {{
+                        ::{std}::ffi::CStr::from_bytes_with_nul_unchecked(&bytes[..nul + 1])
+                    }},
+                    None => panic!("`{name}` is not nul-terminated"),
+                }}
+            }}
+            pub fn set_{name}(&mut self, s: &::{std}::ffi::CStr) {{
+                let bytes = s.to_bytes();
+                let len = ::{std}::cmp::min(bytes.len(), {len} - 1);
+                for (dst, &src) in self.{name}.iter_mut().zip(&bytes[..len]) {{
+                    *dst = src as {elem};
+                }}
+                self.{name}[len] = 0;
+            }}
+        }}
+    "#,
+                           name = f_name,
+                           len = len,
+                           elem = pprust::ty_to_string(elem_ty),
+                           track_caller = track_caller_attr(ctx),
+                           std = ctx.std_crate);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.clone(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+/// The attribute of the generated wrappers that can panic, so that the panics
+/// point at their caller.
+fn track_caller_attr(ctx: &GenCtx) -> &'static str {
+    if ctx.track_caller {
+        "#[track_caller]"
+    } else {
+        ""
+    }
+}
+
+// Views a pointer and length pair of fields as a slice
+fn mk_slice_accessor(ctx: &GenCtx,
+                     ptr_field: &str,
+                     len_field: &str,
+                     elem_ty: &ast::Ty)
+                     -> Option<ast::ImplItem> {
+    let impl_str = format!(r"
+        impl X {{
+            {track_caller}
+            pub unsafe fn {}_slice(&self) -> &[{}] {{
+                if self.{}.is_null() {{
+                    &[]
+                }} else {{
+                    ::{std}::slice::from_raw_parts(self.{}, self.{} as usize)
+                }}
+            }}
+        }}
+    ",
+                           ptr_field,
+                           pprust::ty_to_string(elem_ty),
+                           ptr_field,
+                           ptr_field,
+                           len_field,
+                           track_caller = track_caller_attr(ctx),
+                           std = ctx.std_crate);
+
+    let item = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                 ctx.ext_cx.cfg(),
+                                                 "".to_owned(),
+                                                 impl_str)
+                   .parse_item()
+                   .unwrap()
+                   .unwrap();
+
+    match item.node {
+        ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.last().cloned(),
+        _ => unreachable!("impl parsed to something other than impl"),
+    }
+}
+
+// Checks the size, the alignment and the field offsets reported by clang
+fn mk_layout_test(ctx: &GenCtx,
+                  ty_name: &str,
+                  size: usize,
+                  align: Option<usize>,
+                  field_offsets: &[(String, usize)])
+                  -> P<ast::Item> {
+    let align_assert = match align {
+        Some(align) => {
+            format!("assert_eq!(::{std}::mem::align_of::<{}>(), {}usize);",
+                    ty_name,
+                    align,
+                    std = ctx.std_crate)
+        }
+        None => "".to_owned(),
+    };
+    let offset_asserts: Vec<_> = field_offsets.iter()
+                                              .map(|&(ref field, offset)| {
+                                                  format!("assert_eq!(unsafe {{ &(*(0 as \
+                                                           *const {})).{} as *const _ as \
+                                                           usize }}, {}usize);",
+                                                          ty_name,
+                                                          field,
+                                                          offset)
+                                              })
+                                              .collect();
+    let test_str = format!(r"
+        #[test]
+        fn bindgen_test_layout_{}() {{
+            assert_eq!(::{std}::mem::size_of::<{}>(), {}usize);
+            {}
+            {}
+        }}
+    ",
+                           ty_name,
+                           ty_name,
+                           size,
+                           align_assert,
+                           offset_asserts.join("\n"),
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      test_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+// Implements std::clone::Clone by copying the bytes, for types that are not Copy
+fn mk_bitwise_clone_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
+    let impl_str = format!(r"
+        impl ::{std}::clone::Clone for {} {{
+            fn clone(&self) -> Self {{ unsafe {{ ::{std}::ptr::read(self) }} }}
+        }}
+    ",
+                           ty_name,
+                           std = ctx.std_crate);
 
-    items.push(mk_default_impl(ctx, name));
-    items.extend(extra.into_iter());
-    items
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
 }
 
-// Implements std::clone::Clone using dereferencing
-fn mk_clone_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
+// Implements conversions from and to the raw bytes of a plain-old-data struct
+fn mk_pod_byte_helpers_impl(ctx: &GenCtx, ty_name: &str, size: usize) -> P<ast::Item> {
     let impl_str = format!(r"
-        impl ::std::clone::Clone for {} {{
-            fn clone(&self) -> Self {{ *self }}
+        impl {} {{
+            pub fn as_bytes(&self) -> &[u8] {{
+                unsafe {{ ::{std}::slice::from_raw_parts(self as *const Self as *const u8, {}) }}
+            }}
+            pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {{
+                assert!(bytes.len() >= {});
+                assert!(bytes.as_ptr() as usize % ::{std}::mem::align_of::<Self>() == 0);
+                &*(bytes.as_ptr() as *const Self)
+            }}
         }}
     ",
-                           ty_name);
+                           ty_name,
+                           size,
+                           size,
+                           std = ctx.std_crate);
 
     parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
                                       ctx.ext_cx.cfg(),
@@ -831,7 +2554,7 @@ fn opaque_to_rs(ctx: &mut GenCtx, name: &str) -> P<ast::Item> {
     let id = rust_type_id(ctx, name);
     P(ast::Item {
         ident: ctx.ext_cx.ident_of(&id),
-        attrs: Vec::new(),
+        attrs: original_name_attrs(ctx, name, &id),
         id: ast::DUMMY_NODE_ID,
         node: def,
         vis: ast::Visibility::Public,
@@ -839,6 +2562,46 @@ fn opaque_to_rs(ctx: &mut GenCtx, name: &str) -> P<ast::Item> {
     })
 }
 
+/// Converts a composite type marked as opaque to a struct holding a blob of
+/// its size and alignment.
+fn opaque_blob_to_rs(ctx: &mut GenCtx,
+                     name: &str,
+                     derive_debug: bool,
+                     layout: Layout)
+                     -> Vec<P<ast::Item>> {
+    let data_field = mk_blob_field(ctx, "_bindgen_data_", layout, ctx.span);
+    let def = ast::ItemKind::Struct(ast::VariantData::Struct(vec![data_field], ast::DUMMY_NODE_ID),
+                                    ast::Generics::default());
+
+    // Like the arrays, the blob can't derive past 32 elements.
+    let can_auto_derive = blob_len(layout) <= 32;
+    let id = rust_type_id(ctx, name);
+    let mut attrs = original_name_attrs(ctx, name, &id);
+    attrs.push(mk_repr_attr(ctx, layout));
+    if can_auto_derive {
+        attrs.push(mk_deriving_copy_clone_attr(ctx));
+        if derive_debug {
+            attrs.push(mk_deriving_debug_attr(ctx));
+        }
+    } else {
+        attrs.push(mk_attr(ctx, "derive", &["Copy"]));
+    }
+
+    let mut items = vec![P(ast::Item {
+                             ident: ctx.ext_cx.ident_of(&id),
+                             attrs: attrs,
+                             id: ast::DUMMY_NODE_ID,
+                             node: def,
+                             vis: ast::Visibility::Public,
+                             span: ctx.span,
+                         })];
+    if !can_auto_derive {
+        items.push(mk_clone_impl(ctx, &id));
+    }
+    items.push(mk_default_impl(ctx, &id));
+    items
+}
+
 fn cunion_to_rs(ctx: &mut GenCtx,
                 name: String,
                 options: &BindgenOptions,
@@ -890,33 +2653,39 @@ fn cunion_to_rs(ctx: &mut GenCtx,
                                      }
                                  });
     let union_attrs = {
-        let mut attrs = vec![mk_repr_attr(ctx, layout)];
+        let mut attrs = original_name_attrs(ctx, &name, &union_id);
+        attrs.push(mk_repr_attr(ctx, layout));
         if can_auto_derive {
             attrs.push(mk_deriving_copy_clone_attr(ctx));
-            if derive_debug {
-                attrs.push(mk_deriving_debug_attr(ctx));
-            }
         } else {
             attrs.push(mk_attr(ctx, "derive", &["Copy"]));
         }
         attrs
     };
 
-    let union_def = mk_item(ctx, union_id, def, ast::Visibility::Public, union_attrs);
+    let union_def = mk_item(ctx, union_id.clone(), def, ast::Visibility::Public, union_attrs);
 
+    let mut methods = if options.emit_layout_constants {
+        mk_layout_consts(ctx, layout)
+    } else {
+        vec![]
+    };
+    methods.extend(gen_comp_methods(ctx,
+                                    data_field_name,
+                                    0,
+                                    CompKind::Union,
+                                    layout.packed,
+                                    &members,
+                                    &mut extra,
+                                    options,
+                                    derive_debug)
+                       .into_iter());
     let union_impl = ast::ItemKind::Impl(ast::Unsafety::Normal,
                                          ast::ImplPolarity::Positive,
                                          ast::Generics::default(),
                                          None,
                                          P(cty_to_rs(ctx, &union)),
-                                         gen_comp_methods(ctx,
-                                                          data_field_name,
-                                                          0,
-                                                          CompKind::Union,
-                                                          &members,
-                                                          &mut extra,
-                                                          options,
-                                                          derive_debug));
+                                         methods);
 
     let mut items = vec![union_def,
                          mk_item(ctx,
@@ -926,17 +2695,39 @@ fn cunion_to_rs(ctx: &mut GenCtx,
                                  Vec::new())];
 
     if !can_auto_derive {
-        items.push(mk_clone_impl(ctx, &name));
+        items.push(mk_clone_impl(ctx, &union_id));
+    }
+    if derive_debug {
+        items.push(mk_union_debug_impl(ctx, &union_id));
     }
 
-    items.push(mk_default_impl(ctx, &name[..]));
+    items.push(mk_default_impl(ctx, &union_id));
     items.extend(extra.into_iter());
     items
 }
 
+/// The suffix of the integer literals of the given signedness and size, when
+/// the literals are suffixed.
+fn int_lit_suffix(ctx: &GenCtx, signed: bool, size: usize) -> ast::LitIntType {
+    if !ctx.int_literal_suffixes {
+        return ast::LitIntType::Unsuffixed;
+    }
+    match (signed, size) {
+        (true, 1) => ast::LitIntType::Signed(ast::IntTy::I8),
+        (false, 1) => ast::LitIntType::Unsigned(ast::UintTy::U8),
+        (true, 2) => ast::LitIntType::Signed(ast::IntTy::I16),
+        (false, 2) => ast::LitIntType::Unsigned(ast::UintTy::U16),
+        (true, 4) => ast::LitIntType::Signed(ast::IntTy::I32),
+        (false, 4) => ast::LitIntType::Unsigned(ast::UintTy::U32),
+        (true, 8) => ast::LitIntType::Signed(ast::IntTy::I64),
+        (false, 8) => ast::LitIntType::Unsigned(ast::UintTy::U64),
+        _ => ast::LitIntType::Unsuffixed,
+    }
+}
+
 /// Converts a signed number to AST Expression.
-fn i64_to_int_lit(ctx: &mut GenCtx, value: i64) -> P<ast::Expr> {
-    let int_lit = ast::LitKind::Int(value.abs() as u64, ast::LitIntType::Unsuffixed);
+fn i64_to_int_lit(ctx: &mut GenCtx, value: i64, suffix: ast::LitIntType) -> P<ast::Expr> {
+    let int_lit = ast::LitKind::Int(value.abs() as u64, suffix);
     let expr = ctx.ext_cx.expr_lit(ctx.span, int_lit);
     if value < 0 {
         let negated = ast::ExprKind::Unary(ast::UnOp::Neg, expr);
@@ -947,8 +2738,13 @@ fn i64_to_int_lit(ctx: &mut GenCtx, value: i64) -> P<ast::Expr> {
 }
 
 /// Converts a C const to Rust AST.
-fn const_to_rs(ctx: &mut GenCtx, name: &str, val: i64, val_ty: ast::Ty) -> P<ast::Item> {
-    let int_lit = i64_to_int_lit(ctx, val);
+fn const_to_rs(ctx: &mut GenCtx,
+               name: &str,
+               val: i64,
+               suffix: ast::LitIntType,
+               val_ty: ast::Ty)
+               -> P<ast::Item> {
+    let int_lit = i64_to_int_lit(ctx, val, suffix);
 
     let cst = ast::ItemKind::Const(P(val_ty), int_lit);
 
@@ -977,6 +2773,21 @@ fn enum_size_to_rust_type_name(signed: bool, size: usize) -> &'static str {
     }
 }
 
+/// The signedness and size of the integer type named `name`.
+fn int_type_sign_and_size(name: &str) -> Option<(bool, usize)> {
+    match name {
+        "i8" => Some((true, 1)),
+        "u8" => Some((false, 1)),
+        "i16" => Some((true, 2)),
+        "u16" => Some((false, 2)),
+        "i32" => Some((true, 4)),
+        "u32" => Some((false, 4)),
+        "i64" => Some((true, 8)),
+        "u64" => Some((false, 8)),
+        _ => None,
+    }
+}
+
 fn enum_size_to_unsigned_max_value(size: usize) -> u64 {
     match size {
         1 => std::u8::MAX as u64,
@@ -993,11 +2804,12 @@ fn cenum_value_to_int_lit(ctx: &mut GenCtx,
                           size: usize,
                           value: i64)
                           -> P<ast::Expr> {
+    let suffix = int_lit_suffix(ctx, enum_is_signed, size);
     if enum_is_signed {
-        i64_to_int_lit(ctx, value)
+        i64_to_int_lit(ctx, value, suffix)
     } else {
         let u64_value = value as u64 & enum_size_to_unsigned_max_value(size);
-        let int_lit = ast::LitKind::Int(u64_value, ast::LitIntType::Unsuffixed);
+        let int_lit = ast::LitKind::Int(u64_value, suffix);
         ctx.ext_cx.expr_lit(ctx.span, int_lit)
     }
 }
@@ -1016,21 +2828,55 @@ fn cenum_to_rs(ctx: &mut GenCtx,
     let enum_repr = enum_size_to_rust_type_name(enum_is_signed, layout.size);
     let mut items = vec![];
 
-    if !options.rust_enums {
-        items.push(ctx.ext_cx.item_ty(ctx.span,
-                                      enum_name,
-                                      ctx.ext_cx
-                                         .ty_ident(ctx.span, ctx.ext_cx.ident_of(enum_repr))));
-        for item in enum_items {
-            let value = cenum_value_to_int_lit(ctx, enum_is_signed, layout.size, item.val);
-            items.push(ctx.ext_cx.item_const(ctx.span,
-                                             ctx.ext_cx.ident_of(&item.name),
-                                             enum_ty.clone(),
-                                             value));
-        }
+    let flag_ty = ctx.patterns
+                     .flag_constant_types
+                     .as_ref()
+                     .and_then(|set| set.matches(name).iter().next())
+                     .map(|i| options.flag_constant_types[i].1.clone());
+    if let Some(ref flag_ty) = flag_ty {
+        // The values are truncated to the integer type overriding the enum's.
+        let (is_signed, size) = int_type_sign_and_size(flag_ty)
+                                    .unwrap_or((enum_is_signed, layout.size));
+        return mk_enum_consts(ctx, name, flag_ty, is_signed, size, enum_items);
+    }
+
+    if options.constified_enum_newtype.iter().any(|n| n == name) {
+        return mk_enum_newtype(ctx,
+                               derive_debug,
+                               name,
+                               enum_is_signed,
+                               enum_repr,
+                               layout.size,
+                               enum_items);
+    }
+
+    if matches(&ctx.patterns.bitfield_enums, name) {
+        let mut items = mk_enum_newtype(ctx,
+                                        derive_debug,
+                                        name,
+                                        enum_is_signed,
+                                        enum_repr,
+                                        layout.size,
+                                        enum_items);
+        items.push(mk_bitfield_enum_impl(ctx, name, enum_is_signed, layout.size, enum_items));
+        items.extend(mk_bitfield_enum_ops(ctx, name).into_iter());
         return items;
     }
 
+    if options.enum_with_unknown.iter().any(|n| n == name) {
+        return mk_enum_with_unknown(ctx,
+                                    derive_debug,
+                                    name,
+                                    enum_is_signed,
+                                    enum_repr,
+                                    layout.size,
+                                    enum_items);
+    }
+
+    if !options.rust_enums {
+        return mk_enum_consts(ctx, name, enum_repr, enum_is_signed, layout.size, enum_items);
+    }
+
     let mut variants = vec![];
     let mut found_values = HashMap::new();
 
@@ -1058,7 +2904,7 @@ fn cenum_to_rs(ctx: &mut GenCtx,
         variants.push(respan(ctx.span,
                              ast::Variant_ {
                                  name: name,
-                                 attrs: vec![],
+                                 attrs: doc_attrs(ctx, &item.comment),
                                  data: ast::VariantData::Unit(ast::DUMMY_NODE_ID),
                                  disr_expr: Some(value),
                              }));
@@ -1087,6 +2933,245 @@ fn cenum_to_rs(ctx: &mut GenCtx,
     items
 }
 
+/// Generates a transparent newtype over the enum representation, with the
+/// enum items as associated constants.
+fn mk_enum_newtype(ctx: &mut GenCtx,
+                   derive_debug: bool,
+                   name: &str,
+                   enum_is_signed: bool,
+                   enum_repr: &str,
+                   size: usize,
+                   enum_items: &[EnumItem])
+                   -> Vec<P<ast::Item>> {
+    let struct_str = format!("
+        #[repr(transparent)]
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        pub struct {}(pub {});
+    ",
+                             name,
+                             enum_repr);
+    let mut newtype = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                        ctx.ext_cx.cfg(),
+                                                        "".to_owned(),
+                                                        struct_str)
+                          .parse_item()
+                          .unwrap()
+                          .unwrap();
+    if derive_debug {
+        let debug_attr = mk_deriving_debug_attr(ctx);
+        newtype = newtype.map(|mut item| {
+            item.attrs.push(debug_attr);
+            item
+        });
+    }
+
+    let consts: Vec<String> = enum_items.iter()
+                                        .map(|item| {
+                                            let value = cenum_value_to_int_lit(ctx,
+                                                                               enum_is_signed,
+                                                                               size,
+                                                                               item.val);
+                                            format!("pub const {}: {} = {}({});",
+                                                    item.name,
+                                                    name,
+                                                    name,
+                                                    pprust::expr_to_string(&value))
+                                        })
+                                        .collect();
+    let impl_str = format!("impl {} {{ {} }}", name, consts.join(" "));
+    let consts_impl = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                        ctx.ext_cx.cfg(),
+                                                        "".to_owned(),
+                                                        impl_str)
+                          .parse_item()
+                          .unwrap()
+                          .unwrap();
+
+    vec![newtype, consts_impl]
+}
+
+/// Generates the enum `name` as an alias of `repr` and a constant per item.
+fn mk_enum_consts(ctx: &mut GenCtx,
+                  name: &str,
+                  repr: &str,
+                  is_signed: bool,
+                  size: usize,
+                  enum_items: &[EnumItem])
+                  -> Vec<P<ast::Item>> {
+    let enum_name = ctx.ext_cx.ident_of(name);
+    let enum_ty = ctx.ext_cx.ty_ident(ctx.span, enum_name);
+    let mut items = vec![ctx.ext_cx.item_ty(ctx.span,
+                                            enum_name,
+                                            ctx.ext_cx
+                                               .ty_ident(ctx.span, ctx.ext_cx.ident_of(repr)))];
+    for item in enum_items {
+        let value = cenum_value_to_int_lit(ctx, is_signed, size, item.val);
+        let doc = doc_attrs(ctx, &item.comment);
+        let cst = ctx.ext_cx.item_const(ctx.span,
+                                        ctx.ext_cx.ident_of(&item.name),
+                                        enum_ty.clone(),
+                                        value);
+        items.push(cst.map(|mut cst| {
+            cst.attrs.extend(doc.into_iter());
+            cst
+        }));
+    }
+    items
+}
+
+/// Generates the flag set methods of a bitfield enum newtype.
+fn mk_bitfield_enum_impl(ctx: &mut GenCtx,
+                         name: &str,
+                         enum_is_signed: bool,
+                         size: usize,
+                         enum_items: &[EnumItem])
+                         -> P<ast::Item> {
+    let all = enum_items.iter().fold(0, |acc, item| acc | item.val);
+    let empty = cenum_value_to_int_lit(ctx, enum_is_signed, size, 0);
+    let all = cenum_value_to_int_lit(ctx, enum_is_signed, size, all);
+    let impl_str = format!("
+        impl {name} {{
+            pub fn empty() -> Self {{ {name}({empty}) }}
+            pub fn all() -> Self {{ {name}({all}) }}
+            pub fn contains(&self, other: Self) -> bool {{ (self.0 & other.0) == other.0 }}
+        }}
+    ",
+                           name = name,
+                           empty = pprust::expr_to_string(&empty),
+                           all = pprust::expr_to_string(&all));
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+/// Implements the bitwise operators of the flag enum `name`.
+fn mk_bitfield_enum_ops(ctx: &mut GenCtx, name: &str) -> Vec<P<ast::Item>> {
+    let impls = [format!("
+        impl ::{std}::ops::BitOr for {name} {{
+            type Output = Self;
+            fn bitor(self, other: Self) -> Self {{ {name}(self.0 | other.0) }}
+        }}
+    ",
+                         name = name,
+                         std = ctx.std_crate),
+                 format!("
+        impl ::{std}::ops::BitAnd for {name} {{
+            type Output = Self;
+            fn bitand(self, other: Self) -> Self {{ {name}(self.0 & other.0) }}
+        }}
+    ",
+                         name = name,
+                         std = ctx.std_crate),
+                 format!("
+        impl ::{std}::ops::BitOrAssign for {name} {{
+            fn bitor_assign(&mut self, other: Self) {{ self.0 |= other.0; }}
+        }}
+    ",
+                         name = name,
+                         std = ctx.std_crate),
+                 format!("
+        impl ::{std}::ops::Not for {name} {{
+            type Output = Self;
+            fn not(self) -> Self {{ {name}(!self.0) }}
+        }}
+    ",
+                         name = name,
+                         std = ctx.std_crate)];
+
+    impls.iter()
+         .map(|impl_str| {
+             parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                               ctx.ext_cx.cfg(),
+                                               "".to_owned(),
+                                               impl_str.clone())
+                 .parse_item()
+                 .unwrap()
+                 .unwrap()
+         })
+         .collect()
+}
+
+/// Generates an enum with an `__Unknown` variant holding the values without an
+/// item, and the conversions from and to the enum representation.
+fn mk_enum_with_unknown(ctx: &mut GenCtx,
+                        derive_debug: bool,
+                        name: &str,
+                        enum_is_signed: bool,
+                        enum_repr: &str,
+                        size: usize,
+                        enum_items: &[EnumItem])
+                        -> Vec<P<ast::Item>> {
+    let mut variants = vec![];
+    let mut from_arms = vec![];
+    let mut into_arms = vec![];
+    let mut found_values = HashSet::new();
+    for item in enum_items {
+        let value = cenum_value_to_int_lit(ctx, enum_is_signed, size, item.val);
+        let value = pprust::expr_to_string(&value);
+        variants.push(format!("{},", item.name));
+        if found_values.insert(item.val) {
+            from_arms.push(format!("{} => {}::{},", value, name, item.name));
+        }
+        into_arms.push(format!("{}::{} => {},", name, item.name, value));
+    }
+
+    let enum_str = format!("
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        pub enum {} {{ {} __Unknown({}), }}
+    ",
+                           name,
+                           variants.join(" "),
+                           enum_repr);
+    let from_str = format!("
+        impl ::{std}::convert::From<{repr}> for {name} {{
+            fn from(value: {repr}) -> Self {{
+                match value {{ {arms} value => {name}::__Unknown(value), }}
+            }}
+        }}
+    ",
+                           std = ctx.std_crate,
+                           repr = enum_repr,
+                           name = name,
+                           arms = from_arms.join(" "));
+    let into_str = format!("
+        impl ::{std}::convert::From<{name}> for {repr} {{
+            fn from(value: {name}) -> Self {{
+                match value {{ {arms} {name}::__Unknown(value) => value, }}
+            }}
+        }}
+    ",
+                           std = ctx.std_crate,
+                           repr = enum_repr,
+                           name = name,
+                           arms = into_arms.join(" "));
+
+    let mut items: Vec<_> = [enum_str, from_str, into_str]
+                                .iter()
+                                .map(|item_str| {
+                                    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                                      ctx.ext_cx.cfg(),
+                                                                      "".to_owned(),
+                                                                      item_str.clone())
+                                        .parse_item()
+                                        .unwrap()
+                                        .unwrap()
+                                })
+                                .collect();
+    if derive_debug {
+        let debug_attr = mk_deriving_debug_attr(ctx);
+        items[0] = items[0].clone().map(|mut item| {
+            item.attrs.push(debug_attr);
+            item
+        });
+    }
+    items
+}
+
 /// Generates accessors for fields in nested structs and unions which must be
 /// represented in Rust as an untyped array.  This process may generate
 /// declarations and implementations that must be placed at the root level.
@@ -1096,6 +3181,7 @@ fn gen_comp_methods(ctx: &mut GenCtx,
                     data_field: &str,
                     data_offset: usize,
                     kind: CompKind,
+                    packed: bool,
                     members: &[CompMember],
                     extra: &mut Vec<P<ast::Item>>,
                     options: &BindgenOptions,
@@ -1105,47 +3191,87 @@ fn gen_comp_methods(ctx: &mut GenCtx,
     let mk_field_method = |ctx: &mut GenCtx, f: &FieldInfo, offset: usize| {
         // TODO: Implement bitfield accessors
         if f.bitfields.is_some() {
-            return None;
+            return vec![];
         }
 
         let (f_name, _) = rust_id(ctx, &f.name);
-        let ret_ty = P(cty_to_rs(ctx, &TPtr(Box::new(f.ty.clone()), false, Layout::default())));
+        let by_value = options.union_access_by_value && kind == CompKind::Union &&
+                       f.ty.can_derive_copy(&options.no_copy);
 
-        // When the offset is zero, generate slightly prettier code.
-        let method = {
-            let impl_str = format!(r"
+        let impl_str = if by_value {
+            let field_ty = P(cty_to_rs(ctx, &f.ty));
+            format!(r"
+                impl X {{
+                    pub unsafe fn {name}(&self) -> {ty} {{
+                        let raw: *const u8 = ::{std}::mem::transmute(&self.{data});
+                        ::{std}::ptr::read(raw.offset({offset}) as *const {ty})
+                    }}
+                    pub unsafe fn set_{name}(&mut self, value: {ty}) {{
+                        let raw: *mut u8 = ::{std}::mem::transmute(&mut self.{data});
+                        ::{std}::ptr::write(raw.offset({offset}) as *mut {ty}, value)
+                    }}
+                }}
+            ",
+                    name = f_name,
+                    ty = tts_to_string(&field_ty.to_tokens(&ctx.ext_cx)[..]),
+                    data = data_field,
+                    offset = offset,
+                    std = ctx.std_crate)
+        } else {
+            let ret_ty = P(cty_to_rs(ctx,
+                                     &TPtr(Box::new(f.ty.clone()), false, Layout::default())));
+            format!(r"
                 impl X {{
                     pub unsafe fn {}(&mut self) -> {} {{
-                        let raw: *mut u8 = ::std::mem::transmute(&self.{});
-                        ::std::mem::transmute(raw.offset({}))
+                        let raw: *mut u8 = ::{std}::mem::transmute(&self.{});
+                        ::{std}::mem::transmute(raw.offset({}))
                     }}
                 }}
             ",
-                                   f_name,
-                                   tts_to_string(&ret_ty.to_tokens(&ctx.ext_cx)[..]),
-                                   data_field,
-                                   offset);
-
-            parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
-                                              ctx.ext_cx.cfg(),
-                                              "".to_owned(),
-                                              impl_str)
-                .parse_item()
-                .unwrap()
-                .unwrap()
+                    f_name,
+                    tts_to_string(&ret_ty.to_tokens(&ctx.ext_cx)[..]),
+                    data_field,
+                    offset,
+                    std = ctx.std_crate)
         };
 
-        method.and_then(|i| {
-            match i.node {
-                ast::ItemKind::Impl(_, _, _, _, _, mut items) => items.pop(),
-                _ => unreachable!("impl parsed to something other than impl"),
-            }
-        })
+        let method = parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                                       ctx.ext_cx.cfg(),
+                                                       "".to_owned(),
+                                                       impl_str)
+                         .parse_item()
+                         .unwrap()
+                         .unwrap();
+
+        match method.node {
+            ast::ItemKind::Impl(_, _, _, _, _, ref items) => items.clone(),
+            _ => unreachable!("impl parsed to something other than impl"),
+        }
     };
 
-    let mut offset = data_offset;
+    // The offset of the members from the start of this composite, aligned like
+    // in `cstruct_to_rs` so that the nested anonymous composites and the
+    // members following them are found at any depth.
+    let mut rel_offset = 0;
     let mut methods = vec![];
     for m in members.into_iter() {
+        if kind == CompKind::Struct {
+            let field_offset = match *m {
+                CompMember::Field(ref f) |
+                CompMember::CompField(_, ref f) |
+                CompMember::EnumField(_, ref f) => f.offset,
+                _ => None,
+            };
+            let align = m.layout().align;
+            rel_offset = match field_offset {
+                Some(field_offset) => field_offset,
+                None if !packed && align > 1 && rel_offset % align != 0 => {
+                    rel_offset + align - rel_offset % align
+                }
+                None => rel_offset,
+            };
+        }
+        let offset = data_offset + rel_offset;
         let advance_by = match *m {
             CompMember::Field(ref f) => {
                 methods.extend(mk_field_method(ctx, f, offset).into_iter());
@@ -1157,6 +3283,7 @@ fn gen_comp_methods(ctx: &mut GenCtx,
                                                 data_field,
                                                 offset,
                                                 c.kind,
+                                                c.layout.packed,
                                                 &c.members,
                                                 extra,
                                                 options,
@@ -1174,6 +3301,7 @@ fn gen_comp_methods(ctx: &mut GenCtx,
                                         options,
                                         derive_debug,
                                         c.layout,
+                                        c.opaque,
                                         c.members.clone())
                                  .into_iter());
                 f.ty.size()
@@ -1183,7 +3311,7 @@ fn gen_comp_methods(ctx: &mut GenCtx,
         };
         match kind {
             CompKind::Struct => {
-                offset += advance_by;
+                rel_offset += advance_by;
             }
             CompKind::Union => {}
         }
@@ -1191,14 +3319,74 @@ fn gen_comp_methods(ctx: &mut GenCtx,
     methods
 }
 
+// Implements std::fmt::Debug for the unions by showing their raw bytes, a
+// derived implementation would only show the blob and can't tell which member
+// is active either. Reading the bytes is fine as the blob is plain data.
+fn mk_union_debug_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
+    let impl_str = format!(r#"
+        impl ::{std}::fmt::Debug for {name} {{
+            fn fmt(&self, f: &mut ::{std}::fmt::Formatter) -> ::{std}::fmt::Result {{
+                let bytes: &[u8] = unsafe {{
+                    ::{std}::slice::from_raw_parts(self as *const Self as *const u8,
+                                                   ::{std}::mem::size_of::<Self>())
+                }};
+                f.debug_struct("{name}").field("_bindgen_data_", &bytes).finish()
+            }}
+        }}
+    "#,
+                           name = ty_name,
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+// Implements std::fmt::Debug for the structs with anonymous members past 32
+// elements, showing their blob as a slice.
+fn mk_struct_debug_impl(ctx: &GenCtx, ty_name: &str, fields: &[(String, bool)]) -> P<ast::Item> {
+    let fields: String = fields.iter()
+                               .map(|&(ref name, as_slice)| {
+                                   if as_slice {
+                                       format!(".field(\"{0}\", &&self.{0}[..])", name)
+                                   } else {
+                                       format!(".field(\"{0}\", &self.{0})", name)
+                                   }
+                               })
+                               .collect();
+    let impl_str = format!(r#"
+        impl ::{std}::fmt::Debug for {name} {{
+            fn fmt(&self, f: &mut ::{std}::fmt::Formatter) -> ::{std}::fmt::Result {{
+                f.debug_struct("{name}"){fields}.finish()
+            }}
+        }}
+    "#,
+                           name = ty_name,
+                           fields = fields,
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
 // Implements std::default::Default using std::mem::zeroed.
 fn mk_default_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
     let impl_str = format!(r"
-        impl ::std::default::Default for {} {{
-            fn default() -> Self {{ unsafe {{ ::std::mem::zeroed() }} }}
+        impl ::{std}::default::Default for {} {{
+            fn default() -> Self {{ unsafe {{ ::{std}::mem::zeroed() }} }}
         }}
     ",
-                           ty_name);
+                           ty_name,
+                           std = ctx.std_crate);
 
     parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
                                       ctx.ext_cx.cfg(),
@@ -1209,18 +3397,54 @@ fn mk_default_impl(ctx: &GenCtx, ty_name: &str) -> P<ast::Item> {
         .unwrap()
 }
 
-fn mk_blob_field(ctx: &GenCtx, name: &str, layout: Layout, span: Span) -> ast::StructField {
-    let ty_name = match layout.align {
+// Implements std::default::Default by zeroing the value and passing it to the C
+// initializer function, called by `init`.
+fn mk_initializer_default_impl(ctx: &mut GenCtx, ty_name: &str, init: &str) -> P<ast::Item> {
+    let impl_str = format!(r"
+        impl ::{std}::default::Default for {} {{
+            fn default() -> Self {{
+                unsafe {{
+                    let mut value: Self = ::{std}::mem::zeroed();
+                    {}(&mut value);
+                    value
+                }}
+            }}
+        }}
+    ",
+                           ty_name,
+                           init,
+                           std = ctx.std_crate);
+
+    parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                      ctx.ext_cx.cfg(),
+                                      "".to_owned(),
+                                      impl_str)
+        .parse_item()
+        .unwrap()
+        .unwrap()
+}
+
+fn blob_ty_name(layout: Layout) -> &'static str {
+    match layout.align {
         8 => "u64",
         4 => "u32",
         2 => "u16",
         1 | _ => "u8",
-    };
-    let data_len = if ty_name == "u8" {
+    }
+}
+
+/// The number of elements of the array standing for `layout`.
+fn blob_len(layout: Layout) -> usize {
+    if blob_ty_name(layout) == "u8" {
         layout.size
     } else {
         layout.size / layout.align
-    };
+    }
+}
+
+fn mk_blob_field(ctx: &GenCtx, name: &str, layout: Layout, span: Span) -> ast::StructField {
+    let ty_name = blob_ty_name(layout);
+    let data_len = blob_len(layout);
     let base_ty = mk_ty(ctx, false, vec![ty_name.to_owned()]);
     let data_ty = P(mk_arrty(ctx, &base_ty, data_len));
     ast::StructField {
@@ -1233,8 +3457,108 @@ fn mk_blob_field(ctx: &GenCtx, name: &str, layout: Layout, span: Span) -> ast::S
     }
 }
 
+fn mk_name_value_attr(ctx: &mut GenCtx,
+                      name: &'static str,
+                      value: &str,
+                      is_sugared_doc: bool)
+                      -> ast::Attribute {
+    let lit = respan(ctx.span,
+                     ast::LitKind::Str(ctx.ext_cx.name_of(value).as_str(),
+                                       ast::StrStyle::Cooked));
+    respan(ctx.span,
+           ast::Attribute_ {
+               id: mk_attr_id(),
+               style: ast::AttrStyle::Outer,
+               value: P(respan(ctx.span,
+                               ast::MetaItemKind::NameValue(InternedString::new(name), lit))),
+               is_sugared_doc: is_sugared_doc,
+           })
+}
+
 fn mk_link_name_attr(ctx: &mut GenCtx, name: &str) -> ast::Attribute {
-    mk_attr(ctx, "link_name", &[name])
+    // The leading `\u{1}` tells LLVM to use the symbol name as is.
+    let link_name = if ctx.raw_link_names {
+        format!("\u{1}{}", name)
+    } else {
+        name.to_owned()
+    };
+    mk_name_value_attr(ctx, "link_name", &link_name, false)
+}
+
+fn mk_doc_attr(ctx: &mut GenCtx, doc: &str) -> ast::Attribute {
+    mk_name_value_attr(ctx, "doc", &format!("/// {}", doc), true)
+}
+
+/// The doc attributes of a documentation comment, one per line, when they are
+/// enabled.
+fn doc_attrs(ctx: &mut GenCtx, comment: &str) -> Vec<ast::Attribute> {
+    if !ctx.comments || comment.is_empty() {
+        return vec![];
+    }
+    comment.lines()
+           .map(|line| {
+               if line.is_empty() {
+                   mk_name_value_attr(ctx, "doc", "///", true)
+               } else {
+                   mk_doc_attr(ctx, line)
+               }
+           })
+           .collect()
+}
+
+/// Documents the type definition among `items`, the first struct, enum or type
+/// alias, the others being its implementations and constants.
+fn add_doc_attrs(ctx: &mut GenCtx, items: &mut Vec<P<ast::Item>>, comment: &str) {
+    let doc = doc_attrs(ctx, comment);
+    if doc.is_empty() {
+        return;
+    }
+    let pos = items.iter().position(|item| {
+        match item.node {
+            ast::ItemKind::Struct(..) |
+            ast::ItemKind::Enum(..) |
+            ast::ItemKind::Ty(..) => true,
+            _ => false,
+        }
+    });
+    if let Some(pos) = pos {
+        let item = items.remove(pos).map(|mut item| {
+            item.attrs = doc.into_iter().chain(item.attrs.into_iter()).collect();
+            item
+        });
+        items.insert(pos, item);
+    }
+}
+
+/// A `/// originally: c_name` comment, when it is enabled and the Rust name
+/// differs from the C one.
+fn original_name_attrs(ctx: &mut GenCtx, c_name: &str, rust_name: &str) -> Vec<ast::Attribute> {
+    let mut attrs = vec![];
+    if c_name == rust_name {
+        return attrs;
+    }
+    if ctx.original_name_comments {
+        attrs.push(mk_doc_attr(ctx, &format!("originally: {}", c_name)));
+    }
+    if ctx.doc_alias {
+        attrs.push(mk_doc_alias_attr(ctx, c_name));
+    }
+    attrs
+}
+
+/// `#[doc(alias = "name")]`, so that the docs can be searched by `name`.
+fn mk_doc_alias_attr(ctx: &mut GenCtx, name: &str) -> ast::Attribute {
+    let alias = {
+        let value = ast::LitKind::Str(ctx.ext_cx.name_of(name).as_str(), ast::StrStyle::Cooked);
+        ctx.ext_cx.meta_name_value(ctx.span, InternedString::new("alias"), value)
+    };
+    respan(ctx.span,
+           ast::Attribute_ {
+               id: mk_attr_id(),
+               style: ast::AttrStyle::Outer,
+               value: ctx.ext_cx.meta_list(ctx.span, InternedString::new("doc"), vec![alias]),
+               is_sugared_doc: false,
+           })
 }
 
 fn mk_repr_attr(ctx: &mut GenCtx, layout: Layout) -> ast::Attribute {
@@ -1282,13 +3606,91 @@ fn mk_attr_style(ctx: &mut GenCtx,
            })
 }
 
-fn cvar_to_rs(ctx: &mut GenCtx, name: String, ty: &Type, is_const: bool) -> ast::ForeignItem {
-    let (rust_name, was_mangled) = rust_id(ctx, &name);
+/// Generates the functions reading and writing a volatile variable, or the
+/// volatile memory it points to.
+fn mk_volatile_var_accessors(ctx: &mut GenCtx, v: &VarInfo) -> Vec<P<ast::Item>> {
+    let (ty, place, writable) = if v.is_volatile {
+        (v.ty.clone(), "&", !v.is_const)
+    } else if v.is_volatile_pointee {
+        match v.ty {
+            TPtr(ref pointee, is_const, _) => ((**pointee).clone(), "", !is_const),
+            _ => return vec![],
+        }
+    } else {
+        return vec![];
+    };
+
+    let name = foreign_names(ctx, &v.name).0;
+    let ty = pprust::ty_to_string(&cty_to_rs(ctx, &ty));
+    let mut fns = vec![format!("
+        pub unsafe fn read_{name}() -> {ty} {{
+            ::{std}::ptr::read_volatile({place}{name})
+        }}
+    ",
+                               name = name,
+                               ty = ty,
+                               place = place,
+                               std = ctx.std_crate)];
+    if writable {
+        fns.push(format!("
+            pub unsafe fn write_{name}(value: {ty}) {{
+                ::{std}::ptr::write_volatile({place}{name}, value)
+            }}
+        ",
+                         name = name,
+                         ty = ty,
+                         place = if place.is_empty() { "" } else { "&mut " },
+                         std = ctx.std_crate));
+    }
+
+    fns.into_iter()
+       .map(|fn_str| {
+           parse::new_parser_from_source_str(ctx.ext_cx.parse_sess(),
+                                             ctx.ext_cx.cfg(),
+                                             "".to_owned(),
+                                             fn_str)
+               .parse_item()
+               .unwrap()
+               .unwrap()
+       })
+       .collect()
+}
 
-    let mut attrs = Vec::new();
-    if was_mangled {
-        attrs.push(mk_link_name_attr(ctx, &name));
+/// `name` without `prefix`, unless nothing would be left.
+fn strip_name<'a>(prefix: &str, name: &'a str) -> &'a str {
+    if !prefix.is_empty() && name.starts_with(prefix) && name.len() > prefix.len() {
+        &name[prefix.len()..]
+    } else {
+        name
+    }
+}
+
+/// The Rust name of the function or variable `name`, and its attributes
+/// linking it to the exported symbol.
+fn foreign_names(ctx: &mut GenCtx, name: &str) -> (String, Vec<ast::Attribute>) {
+    let symbol = format!("{}{}", ctx.link_prefix, name);
+    foreign_symbol_names(ctx, name, &symbol)
+}
+
+/// Like `foreign_names`, linking to the given symbol.
+fn foreign_symbol_names(ctx: &mut GenCtx,
+                        name: &str,
+                        symbol: &str)
+                        -> (String, Vec<ast::Attribute>) {
+    let (rust_name, was_mangled) = {
+        let stripped = strip_name(&ctx.strip_prefix, name).to_owned();
+        rust_id(ctx, &stripped)
+    };
+
+    let mut attrs = original_name_attrs(ctx, name, &rust_name);
+    if was_mangled || ctx.raw_link_names || rust_name != symbol {
+        attrs.push(mk_link_name_attr(ctx, symbol));
     }
+    (rust_name, attrs)
+}
+
+fn cvar_to_rs(ctx: &mut GenCtx, name: String, ty: &Type, is_const: bool) -> ast::ForeignItem {
+    let (rust_name, attrs) = foreign_names(ctx, &name);
 
     let node = {
         let val_ty = P(cty_to_rs(ctx, ty));
@@ -1358,6 +3760,7 @@ fn cfuncty_to_rs(ctx: &mut GenCtx, rty: &Type, aty: &[(String, Type)], var: bool
 
 fn cfunc_to_rs(ctx: &mut GenCtx,
                name: String,
+               mangled: Option<&str>,
                rty: &Type,
                aty: &[(String, Type)],
                var: bool)
@@ -1366,18 +3769,45 @@ fn cfunc_to_rs(ctx: &mut GenCtx,
     let decl = ast::ForeignItemKind::Fn(P(cfuncty_to_rs(ctx, rty, aty, var)),
                                         ast::Generics::default());
 
-    let (rust_name, was_mangled) = rust_id(ctx, &name);
-
-    let mut attrs = Vec::new();
-    if was_mangled {
-        attrs.push(mk_link_name_attr(ctx, &name));
-    }
+    let (rust_name, attrs) = match mangled {
+        Some(symbol) => foreign_symbol_names(ctx, &name, symbol),
+        None => foreign_names(ctx, &name),
+    };
 
     mk_foreign_item(ctx, &rust_name, attrs, decl)
 }
 
+/// Makes the functions never returning return `!`, and documents the `pure` and
+/// `const` ones.
+fn add_function_attributes(ctx: &mut GenCtx, item: &mut ast::ForeignItem, v: &VarInfo) {
+    if v.is_noreturn {
+        if let ast::ForeignItemKind::Fn(ref mut decl, _) = item.node {
+            let span = ctx.span;
+            *decl = decl.clone().map(|mut decl| {
+                decl.output = ast::FunctionRetTy::None(span);
+                decl
+            });
+        }
+    }
+    if !ctx.function_attribute_docs {
+        return;
+    }
+    if v.is_const_fn {
+        item.attrs.push(mk_doc_attr(ctx, "`__attribute__((const))`: only depends on its arguments."));
+    } else if v.is_pure {
+        item.attrs.push(mk_doc_attr(ctx, "`__attribute__((pure))`: has no side effects."));
+    }
+}
+
 fn cty_to_rs(ctx: &mut GenCtx, ty: &Type) -> ast::Ty {
-    let prefix = vec!["std".to_owned(), "os".to_owned(), "raw".to_owned()];
+    let global = !ctx.prelude;
+    let prefix = if ctx.prelude {
+        vec![]
+    } else if ctx.std_crate == "core" {
+        vec!["core".to_owned(), "ffi".to_owned()]
+    } else {
+        vec!["std".to_owned(), "os".to_owned(), "raw".to_owned()]
+    };
     let raw = |fragment: &str| {
         let mut path = prefix.clone();
         path.push(fragment.to_owned());
@@ -1385,7 +3815,7 @@ fn cty_to_rs(ctx: &mut GenCtx, ty: &Type) -> ast::Ty {
     };
 
     match *ty {
-        TVoid => mk_ty(ctx, true, raw("c_void")),
+        TVoid => mk_ty(ctx, global, raw("c_void")),
         TInt(i, ref layout) => {
             match i {
                 IBool => {
@@ -1397,22 +3827,50 @@ fn cty_to_rs(ctx: &mut GenCtx, ty: &Type) -> ast::Ty {
                     };
                     mk_ty(ctx, false, vec![ty_name.to_owned()])
                 }
-                ISChar => mk_ty(ctx, true, raw("c_char")),
-                IUChar => mk_ty(ctx, true, raw("c_uchar")),
-                IInt => mk_ty(ctx, true, raw("c_int")),
-                IUInt => mk_ty(ctx, true, raw("c_uint")),
-                IShort => mk_ty(ctx, true, raw("c_short")),
-                IUShort => mk_ty(ctx, true, raw("c_ushort")),
-                ILong => mk_ty(ctx, true, raw("c_long")),
-                IULong => mk_ty(ctx, true, raw("c_ulong")),
-                ILongLong => mk_ty(ctx, true, raw("c_longlong")),
-                IULongLong => mk_ty(ctx, true, raw("c_ulonglong")),
+                ISChar | IUChar | IInt | IUInt | IShort | IUShort | ILong | IULong |
+                ILongLong | IULongLong if ctx.fixed_width_integers => {
+                    // The layout comes from clang, so it has the width on the
+                    // target rather than on the host.
+                    let sign = if i.is_signed() { "i" } else { "u" };
+                    mk_ty(ctx, false, vec![format!("{}{}", sign, layout.size * 8)])
+                }
+                ISChar => mk_ty(ctx, global, raw("c_char")),
+                IUChar => mk_ty(ctx, global, raw("c_uchar")),
+                IInt => mk_ty(ctx, global, raw("c_int")),
+                IUInt => mk_ty(ctx, global, raw("c_uint")),
+                IShort => mk_ty(ctx, global, raw("c_short")),
+                IUShort => mk_ty(ctx, global, raw("c_ushort")),
+                ILong => mk_ty(ctx, global, raw("c_long")),
+                IULong => mk_ty(ctx, global, raw("c_ulong")),
+                ILongLong => mk_ty(ctx, global, raw("c_longlong")),
+                IULongLong => mk_ty(ctx, global, raw("c_ulonglong")),
+                IWChar(signed) => {
+                    // `wchar_t` is 16 bits unsigned on Windows, and 32 bits
+                    // signed or unsigned on the other platforms.
+                    let ty_name = match (layout.size, signed) {
+                        (2, false) => "u16",
+                        (2, true) => "i16",
+                        (_, false) => "u32",
+                        (_, true) => "i32",
+                    };
+                    mk_ty(ctx, false, vec![ty_name.to_owned()])
+                }
+                IChar16 => mk_ty(ctx, false, vec!["u16".to_owned()]),
+                IChar32 => mk_ty(ctx, false, vec!["u32".to_owned()]),
             }
         }
         TFloat(f, _) => {
             match f {
-                FFloat => mk_ty(ctx, true, raw("c_float")),
-                FDouble => mk_ty(ctx, true, raw("c_double")),
+                FFloat => mk_ty(ctx, global, raw("c_float")),
+                FDouble => mk_ty(ctx, global, raw("c_double")),
+                FFloat16 => {
+                    if ctx.float16_as_f16 {
+                        mk_ty(ctx, false, vec!["f16".to_owned()])
+                    } else {
+                        ctx.uses_float16 = true;
+                        mk_helper_ty(ctx, "__BindgenFloat16")
+                    }
+                }
             }
         }
         TPtr(ref t, is_const, _) => {
@@ -1524,12 +3982,16 @@ fn mk_fnty(ctx: &mut GenCtx, decl: ast::FnDecl, unsafety: ast::Unsafety, abi: ab
         decl: P(decl.clone()),
     }));
 
-    let idents = ["std", "option", "Option"]
-                     .iter()
+    let path = if ctx.prelude {
+        vec!["Option"]
+    } else {
+        vec![ctx.std_crate, "option", "Option"]
+    };
+    let idents = path.iter()
                      .map(|item| ctx.ext_cx.ident_of(item))
                      .collect();
     let types = vec![ctx.ext_cx.ty(ctx.span, fnty)];
     ctx.ext_cx
-       .ty_path(ctx.ext_cx.path_all(ctx.span, true, idents, Vec::new(), types, Vec::new()))
+       .ty_path(ctx.ext_cx.path_all(ctx.span, !ctx.prelude, idents, Vec::new(), types, Vec::new()))
        .unwrap()
 }
@@ -9,17 +9,22 @@ extern crate syntex_syntax as syntax;
 extern crate libc;
 #[macro_use]
 extern crate log;
+extern crate regex;
 
+use std::cell::Cell;
 use std::collections::HashSet;
 use std::default::Default;
-use std::io::{self, Write};
-use std::fs::OpenOptions;
+use std::error;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::fs::{File, OpenOptions};
 use std::path::Path;
+use std::process::Command;
 
 use syntax::ast;
 use syntax::codemap::{DUMMY_SP, Span};
 use syntax::print::pprust;
-use syntax::print::pp::eof;
+use syntax::print::pp::{self, eof};
 use syntax::ptr::P;
 
 use types::Global;
@@ -42,9 +47,11 @@ pub fn builder<'a>() -> Builder<'a> {
 }
 
 impl<'a> Builder<'a> {
-    /// Add a C header to parse.
+    /// Add a C header to parse, `-` reading it from the standard input. The
+    /// headers are parsed in order into the same bindings.
     pub fn header<T: Into<String>>(&mut self, header: T) -> &mut Self {
-        self.clang_arg(header)
+        self.options.headers.push(header.into());
+        self
     }
 
     pub fn match_pat<T: Into<String>>(&mut self, arg: T) -> &mut Self {
@@ -87,6 +94,556 @@ impl<'a> Builder<'a> {
         self
     }
 
+    /// Emit `wchar_t` as `u32` when it is 32 bits wide, even on the platforms
+    /// where it is signed.
+    pub fn wchar_as_u32(&mut self, value: bool) -> &mut Self {
+        self.options.wchar_as_u32 = value;
+        self
+    }
+
+    /// Sort the functions of the generated `extern` blocks by name. Types
+    /// are still emitted in declaration order.
+    pub fn sort_functions_by_name(&mut self, value: bool) -> &mut Self {
+        self.options.sort_functions_by_name = value;
+        self
+    }
+
+    /// Generate `as_bytes`/`from_bytes` helpers for the struct named `name`.
+    /// Structs containing pointers are skipped with a warning. `from_bytes` panics
+    /// on a slice that is too short or not aligned for the struct.
+    pub fn pod_byte_helpers<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        self.options.pod_byte_helpers.push(name.into());
+        self
+    }
+
+    /// Instead of `extern` blocks, generate a `DynamicLibrary` struct whose
+    /// functions are loaded at runtime with the `libloading` crate. It needs
+    /// `std`, so it cannot be used together with `use_core_ffi`.
+    pub fn dynamic_library_runtime(&mut self, value: bool) -> &mut Self {
+        self.options.dynamic_library_runtime = value;
+        self
+    }
+
+    /// Do not derive `Copy` for the type named `name`, nor for the structs
+    /// containing it.
+    pub fn no_copy<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        self.options.no_copy.push(name.into());
+        self
+    }
+
+    /// Fail the generation if any warning is emitted.
+    pub fn treat_warnings_as_errors(&mut self, value: bool) -> &mut Self {
+        self.options.treat_warnings_as_errors = value;
+        self
+    }
+
+    /// Forward the warnings reported by clang while parsing to the logger.
+    /// Clang errors and the `#warning` directives are always reported.
+    pub fn emit_clang_diagnostics(&mut self, value: bool) -> &mut Self {
+        self.options.emit_clang_diagnostics = value;
+        self
+    }
+
+    /// Generate a `#[test]` per struct checking its size, alignment and field
+    /// offsets against the ones reported by clang.
+    pub fn layout_offset_tests(&mut self, value: bool) -> &mut Self {
+        self.options.layout_offset_tests = value;
+        self
+    }
+
+    /// Set the visibility of the generated struct fields. Fields which are not
+    /// `pub` get a `get_` accessor.
+    pub fn emit_field_visibility(&mut self, vis: Visibility) -> &mut Self {
+        self.options.field_visibility = vis;
+        self
+    }
+
+    /// Override the field visibility for the struct `name`, or for a single
+    /// field when `name` is of the form `Struct::field`.
+    pub fn field_visibility<T: Into<String>>(&mut self, name: T, vis: Visibility) -> &mut Self {
+        self.options.field_visibility_overrides.push((name.into(), vis));
+        self
+    }
+
+    /// Keep the C names verbatim, even when they shadow a Rust primitive type.
+    /// Only the Rust keywords are still escaped.
+    pub fn c_naming(&mut self, value: bool) -> &mut Self {
+        self.options.c_naming = value;
+        self
+    }
+
+    /// Turn the macros aliasing a generated item, like `#define foo bar`, into
+    /// `pub use` declarations or type aliases.
+    pub fn alias_defines(&mut self, value: bool) -> &mut Self {
+        self.options.alias_defines = value;
+        self
+    }
+
+    /// Allow the `dead_code` lint for the whole generated module.
+    pub fn allow_dead_code(&mut self, value: bool) -> &mut Self {
+        self.options.allow_dead_code = value;
+        self
+    }
+
+    /// Allow the `dead_code` lint on every generated item, for bindings pasted
+    /// in a module which can't carry the inner attributes.
+    pub fn allow_dead_code_per_item(&mut self, value: bool) -> &mut Self {
+        self.options.allow_dead_code_per_item = value;
+        self
+    }
+
+    /// Map `_Float16` to `f16` instead of the generated `__BindgenFloat16`,
+    /// for toolchains supporting it.
+    pub fn with_float_mapping(&mut self, f16: bool) -> &mut Self {
+        self.options.float16_as_f16 = f16;
+        self
+    }
+
+    /// Strip `prefix` from the generated names, keeping the full name of the
+    /// items whose short name is already taken, with a warning. The renamed
+    /// functions and variables keep linking to their symbol.
+    pub fn trim_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut Self {
+        self.options.trim_prefix = prefix.into();
+        self
+    }
+
+    /// Keep the full names for the definitions and `pub use` them under their
+    /// name without the prefix, skipping the ambiguous ones.
+    pub fn trim_prefix_reexport(&mut self, value: bool) -> &mut Self {
+        self.options.trim_prefix_reexport = value;
+        self
+    }
+
+    /// Generate a `<ptr_field>_slice` method on the struct `name`, viewing its
+    /// `ptr_field` pointer and `len_field` length as a slice.
+    pub fn slice_accessors<T: Into<String>>(&mut self,
+                                            name: T,
+                                            ptr_field: T,
+                                            len_field: T)
+                                            -> &mut Self {
+        self.options.slice_accessors.push((name.into(), ptr_field.into(), len_field.into()));
+        self
+    }
+
+    /// Write a hash of the options and of the headers in the output, to detect
+    /// when the bindings need to be regenerated.
+    pub fn emit_generated_hash(&mut self, value: bool) -> &mut Self {
+        self.options.emit_generated_hash = value;
+        self
+    }
+
+    /// Separate the types, constants, variables and functions with section
+    /// comments in the output.
+    pub fn generate_block_comments(&mut self, value: bool) -> &mut Self {
+        self.options.generate_block_comments = value;
+        self
+    }
+
+    /// Emit a `#[link_name]` prefixed with `\u{1}` for every function and
+    /// variable, so that their symbol is used as is.
+    pub fn raw_link_names(&mut self, value: bool) -> &mut Self {
+        self.options.raw_link_names = value;
+        self
+    }
+
+    /// Don't generate the items matching the regular expression `pattern`,
+    /// whatever their kind. The types still referred to by the other items are
+    /// generated as opaque blobs instead, with a warning.
+    pub fn blocklist_item<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.blocklist_item.push(pattern.into());
+        self
+    }
+
+    /// Document the original C name of the items and fields which had to be
+    /// renamed.
+    pub fn emit_original_name_comment(&mut self, value: bool) -> &mut Self {
+        self.options.emit_original_name_comment = value;
+        self
+    }
+
+    /// Turn the documentation comments of the C declarations into doc comments.
+    pub fn generate_comments(&mut self, value: bool) -> &mut Self {
+        self.options.generate_comments = value;
+        self
+    }
+
+    /// Emit the C integer types with their width on the target, like `i32` or
+    /// `i64` for `long`, instead of the `c_long` adapting to the host.
+    pub fn fixed_width_integers(&mut self, value: bool) -> &mut Self {
+        self.options.fixed_width_integers = value;
+        self
+    }
+
+    /// Parse the headers for the target `triple`, emitting the C integer types
+    /// with their width on that target. A later call replaces the target.
+    pub fn clang_target_env<T: Into<String>>(&mut self, triple: T) -> &mut Self {
+        let triple = triple.into();
+        let pos = self.options.clang_args.iter().position(|a| a == "-target");
+        match pos {
+            Some(i) if i + 1 < self.options.clang_args.len() => {
+                self.options.clang_args[i + 1] = triple;
+            }
+            _ => {
+                self.options.clang_args.push("-target".to_owned());
+                self.options.clang_args.push(triple);
+            }
+        }
+        self.fixed_width_integers(true)
+    }
+
+    /// Parse the headers for `wasm32-unknown-unknown`.
+    pub fn for_wasm32(&mut self) -> &mut Self {
+        self.clang_target_env("wasm32-unknown-unknown")
+    }
+
+    /// Parse the headers for `aarch64-unknown-linux-gnu`.
+    pub fn for_aarch64_linux(&mut self) -> &mut Self {
+        self.clang_target_env("aarch64-unknown-linux-gnu")
+    }
+
+    /// Parse the headers for `armv7-unknown-linux-gnueabihf`.
+    pub fn for_armv7_linux(&mut self) -> &mut Self {
+        self.clang_target_env("armv7-unknown-linux-gnueabihf")
+    }
+
+    /// Parse the headers for `x86_64-pc-windows-msvc`, where `long` is 32 bits.
+    pub fn for_x86_64_windows(&mut self) -> &mut Self {
+        self.clang_target_env("x86_64-pc-windows-msvc")
+    }
+
+    /// Emit the size and alignment of the structs and unions reported by clang
+    /// as their `SIZE` and `ALIGN` associated constants.
+    pub fn emit_layout_constants(&mut self, value: bool) -> &mut Self {
+        self.options.emit_layout_constants = value;
+        self
+    }
+
+    /// Generate the structs and unions matching the regular expression
+    /// `pattern` as a blob of their size and alignment, without translating
+    /// their fields.
+    pub fn opaque_type<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.opaque_types.push(pattern.into());
+        self
+    }
+
+    /// Generate a constant for every object-like macro expanding to an integer,
+    /// floating-point, character or string literal.
+    pub fn generate_macro_constants(&mut self, value: bool) -> &mut Self {
+        self.options.generate_macro_constants = value;
+        self
+    }
+
+    /// Make the functions marked `noreturn` return `!`, and document the ones
+    /// marked `pure` or `const`.
+    pub fn emit_function_attributes(&mut self, value: bool) -> &mut Self {
+        self.options.emit_function_attributes = value;
+        self
+    }
+
+    /// Link the functions and variables to their symbol prefixed with
+    /// `prefix`, keeping their C name in Rust.
+    pub fn link_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut Self {
+        self.options.link_prefix = prefix.into();
+        self
+    }
+
+    /// Remove `prefix` from the Rust names of the functions and variables,
+    /// still linking them to their C symbol.
+    pub fn strip_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut Self {
+        self.options.strip_prefix = prefix.into();
+        self
+    }
+
+    /// Wrap in `MaybeUninit` the struct fields for which all zeroes is invalid,
+    /// so that the zeroed `Default` stays sound, and access them with a
+    /// `get_<field>` and `set_<field>` method.
+    pub fn maybe_uninit_fields(&mut self, value: bool) -> &mut Self {
+        self.options.maybe_uninit_fields = value;
+        self
+    }
+
+    /// Implement `Deref` and `DerefMut` to the C++ base class, embedded as the
+    /// `_base` field.
+    pub fn emit_base_deref(&mut self, value: bool) -> &mut Self {
+        self.options.emit_base_deref = value;
+        self
+    }
+
+    /// Load the declarations from the clang precompiled header at `path`, made
+    /// with `clang -x c-header`, before parsing the headers.
+    pub fn pch<T: Into<String>>(&mut self, path: T) -> &mut Self {
+        self.options.pch = Some(path.into());
+        self
+    }
+
+    /// Generate a `<field>_cstr` getter and a `set_<field>` setter viewing the
+    /// `char` array fields of the structs matching the regular expression
+    /// `pattern` as C strings, or of single fields when it matches their
+    /// `Struct::field` path.
+    pub fn char_array_as_cstr<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.cstr_fields.push(pattern.into());
+        self
+    }
+
+    /// Write `line` verbatim at the top of the bindings, like an `extern crate`
+    /// or a `use`.
+    pub fn raw_line<T: Into<String>>(&mut self, line: T) -> &mut Self {
+        self.options.raw_lines.push((None, line.into()));
+        self
+    }
+
+    /// Write `line` like `raw_line`, only when `feature` is enabled with
+    /// `Builder::feature`.
+    pub fn raw_line_if<T: Into<String>>(&mut self, feature: T, line: T) -> &mut Self {
+        self.options.raw_lines.push((Some(feature.into()), line.into()));
+        self
+    }
+
+    /// Enable `feature` for the conditional raw lines.
+    pub fn feature<T: Into<String>>(&mut self, feature: T) -> &mut Self {
+        self.options.features.push(feature.into());
+        self
+    }
+
+    /// Import the C types and `Option` at the top of the bindings, from `std`
+    /// or `core` with `use_core_ffi`, and name them without their path.
+    pub fn emit_prelude(&mut self, value: bool) -> &mut Self {
+        self.options.emit_prelude = value;
+        self
+    }
+
+    /// Generate the functions whose name starts with `prefix` in the module
+    /// `module`, without the prefix.
+    pub fn function_prefix_module<T: Into<String>>(&mut self, prefix: T, module: T) -> &mut Self {
+        self.options.function_prefix_modules.push((prefix.into(), module.into()));
+        self
+    }
+
+    /// Use the given clang resource directory for the builtin headers, in
+    /// place of the one found from the clang install.
+    pub fn clang_resource_dir<T: Into<String>>(&mut self, path: T) -> &mut Self {
+        let path = path.into();
+        let pos = self.options.clang_args.iter().position(|a| a == "-resource-dir");
+        match pos {
+            Some(i) if i + 1 < self.options.clang_args.len() => {
+                self.options.clang_args[i + 1] = path;
+            }
+            _ => {
+                self.options.clang_args.push("-resource-dir".to_owned());
+                self.options.clang_args.push(path);
+            }
+        }
+        self
+    }
+
+    /// Generate the typedefs of fixed arrays as newtypes over the array,
+    /// implementing `Index` and `IndexMut` like the array.
+    pub fn array_newtype_indexing(&mut self, value: bool) -> &mut Self {
+        self.options.array_newtype_indexing = value;
+        self
+    }
+
+    /// Make the union accessors of the `Copy` members read and write the
+    /// member by value, instead of returning a pointer to it.
+    pub fn emit_union_access_by_value(&mut self, value: bool) -> &mut Self {
+        self.options.union_access_by_value = value;
+        self
+    }
+
+    /// Warn about each type pulled in by the whitelists only because a
+    /// whitelisted item refers to it, naming the referring item.
+    pub fn emit_recursive_allowlist_report(&mut self, value: bool) -> &mut Self {
+        self.options.recursive_allowlist_report = value;
+        self
+    }
+
+    /// Generate the C++ methods as functions named `Class_method`, taking the
+    /// object as `this: *const Class` for the `const` methods and as
+    /// `this: *mut Class` otherwise. The overloads after the first one are
+    /// numbered, `Class_method1` and so on.
+    pub fn generate_cxx_methods(&mut self, value: bool) -> &mut Self {
+        self.options.cxx_methods = value;
+        self
+    }
+
+    /// Implement `Hash` by hand for the structs with float fields asking for it
+    /// with `Builder::derive`, hashing the floats by their bit patterns. `-0.0`
+    /// is hashed as `0.0`, as they compare equal.
+    pub fn manual_hash_floats(&mut self, value: bool) -> &mut Self {
+        self.options.manual_hash_floats = value;
+        self
+    }
+
+    /// Give the items renamed from their C name a `#[doc(alias)]` with the C
+    /// name, so that the docs can be searched by it.
+    pub fn emit_doc_alias(&mut self, value: bool) -> &mut Self {
+        self.options.emit_doc_alias = value;
+        self
+    }
+
+    /// Implement `Default` for the struct `ty` by zeroing it and passing it to
+    /// its C initializer function `init`, like `void foo_init(struct foo *)`.
+    /// When `init` isn't generated as a function, the struct is only zeroed,
+    /// with a warning.
+    pub fn associate_initializer<T: Into<String>, U: Into<String>>(&mut self,
+                                                                    ty: T,
+                                                                    init: U)
+                                                                    -> &mut Self {
+        self.options.initializers.push((ty.into(), init.into()));
+        self
+    }
+
+    /// Mark the generated wrappers that can panic, like the slice and C string
+    /// accessors, `#[track_caller]`. It needs Rust 1.46 or later.
+    pub fn emit_track_caller(&mut self, value: bool) -> &mut Self {
+        self.options.emit_track_caller = value;
+        self
+    }
+
+    /// Leave out the section banners and the generated documentation, for the
+    /// smallest output. The generated-by header is kept.
+    pub fn strip_comments_from_output(&mut self, value: bool) -> &mut Self {
+        self.options.strip_comments = value;
+        self
+    }
+
+    /// Generate the enum named `name` as a newtype over its representation,
+    /// with its items as associated constants.
+    pub fn constified_enum_as_newtype<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        self.options.constified_enum_newtype.push(name.into());
+        self
+    }
+
+    /// Set the prefix of the fields holding anonymous structs and unions,
+    /// `_bindgen_data_` by default.
+    pub fn with_anon_field_prefix<T: Into<String>>(&mut self, prefix: T) -> &mut Self {
+        self.options.anon_field_prefix = prefix.into();
+        self
+    }
+
+    /// Take the C types, `Option` and the other helpers from `core` instead of
+    /// `std`.
+    pub fn use_core_ffi(&mut self, value: bool) -> &mut Self {
+        self.options.use_core_ffi = value;
+        self
+    }
+
+    /// Write the crate-level attributes, like `#![no_std]` with
+    /// `use_core_ffi`, for bindings compiled as their own crate.
+    pub fn emit_crate_attributes(&mut self, value: bool) -> &mut Self {
+        self.options.emit_crate_attributes = value;
+        self
+    }
+
+    /// Generate the enum named `name` as a Rust enum with an `__Unknown`
+    /// variant for the values without an item.
+    pub fn enum_with_unknown<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        self.options.enum_with_unknown.push(name.into());
+        self
+    }
+
+    /// Generate the functions reading and writing the volatile variables and
+    /// fields with `read_volatile` and `write_volatile`.
+    pub fn volatile_accessors(&mut self, value: bool) -> &mut Self {
+        self.options.volatile_accessors = value;
+        self
+    }
+
+    /// Generate the helper types, like `__BindgenFloat16`, in the
+    /// `pub(crate)` module `name`.
+    pub fn helpers_in_module<T: Into<String>>(&mut self, name: T) -> &mut Self {
+        self.options.helpers_module = Some(name.into());
+        self
+    }
+
+    /// Suffix the enum discriminants and the integer constants with their type,
+    /// like `0u32`.
+    pub fn with_enum_discriminant_type_suffix(&mut self, value: bool) -> &mut Self {
+        self.options.enum_discriminant_type_suffix = value;
+        self
+    }
+
+    /// Generate the functions.
+    pub fn emit_functions(&mut self, value: bool) -> &mut Self {
+        self.options.functions = value;
+        self
+    }
+
+    /// Generate the enums.
+    pub fn emit_enums(&mut self, value: bool) -> &mut Self {
+        self.options.enums = value;
+        self
+    }
+
+    /// Generate the global variables and constants.
+    pub fn emit_globals(&mut self, value: bool) -> &mut Self {
+        self.options.globals = value;
+        self
+    }
+
+    /// Generate the structs, unions and typedefs.
+    pub fn emit_types(&mut self, value: bool) -> &mut Self {
+        self.options.types = value;
+        self
+    }
+
+    /// Record the files the generated items come from, see
+    /// `Bindings::source_files`.
+    pub fn record_matched_files(&mut self, value: bool) -> &mut Self {
+        self.options.record_matched_files = value;
+        self
+    }
+
+    /// Only generate the types whose name matches the regular expression
+    /// `pattern`, and the types the other generated items depend on.
+    pub fn whitelist_type<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.whitelist_types.push(pattern.into());
+        self
+    }
+
+    /// Only generate the functions whose name matches the regular expression
+    /// `pattern`.
+    pub fn whitelist_function<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.whitelist_functions.push(pattern.into());
+        self
+    }
+
+    /// Only generate the variables whose name matches the regular expression
+    /// `pattern`.
+    pub fn whitelist_var<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.whitelist_vars.push(pattern.into());
+        self
+    }
+
+    /// Generate the enums matching the regular expression `pattern` as sets of
+    /// flags: a newtype with the items as associated constants, `empty`, `all`
+    /// and `contains`, and the bitwise operators.
+    pub fn bitfield_enum<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.bitfield_enums.push(pattern.into());
+        self
+    }
+
+    /// Generate the enums matching the regular expression `pattern` as sets of
+    /// flag constants of the integer type `ty`, like `u32`, instead of the type
+    /// of the enum. The first matching pattern wins.
+    pub fn emit_enum_as_flag_constants_with_type<T: Into<String>>(&mut self,
+                                                                  pattern: T,
+                                                                  ty: T)
+                                                                  -> &mut Self {
+        self.options.flag_constant_types.push((pattern.into(), ty.into()));
+        self
+    }
+
+    /// Derive the traits matching the regular expression `pattern` on the
+    /// structs as well, among `PartialEq`, `Eq`, `Hash` and `Default`. The
+    /// structs having a field that can't support one are left without it, with
+    /// a warning. The patterns matching none of these traits are warned about
+    /// too.
+    pub fn derive<T: Into<String>>(&mut self, pattern: T) -> &mut Self {
+        self.options.derives.push(pattern.into());
+        self
+    }
+
     /// Set the logger to use.
     pub fn log(&mut self, logger: &'a Logger) -> &mut Self {
         self.logger = Some(logger);
@@ -105,7 +662,7 @@ impl<'a> Builder<'a> {
     }
 
     /// Generate the binding using the options previously set.
-    pub fn generate(&self) -> Result<Bindings, ()> {
+    pub fn generate(&self) -> Result<Bindings, BindgenError> {
         Bindings::generate(&self.options, self.logger, None)
     }
 }
@@ -132,6 +689,74 @@ pub struct BindgenOptions {
     pub override_enum_ty: String,
     pub clang_args: Vec<String>,
     pub derive_debug: bool,
+    pub wchar_as_u32: bool,
+    pub sort_functions_by_name: bool,
+    pub pod_byte_helpers: Vec<String>,
+    pub dynamic_library_runtime: bool,
+    pub no_copy: Vec<String>,
+    pub treat_warnings_as_errors: bool,
+    pub emit_clang_diagnostics: bool,
+    pub layout_offset_tests: bool,
+    pub field_visibility: Visibility,
+    pub field_visibility_overrides: Vec<(String, Visibility)>,
+    pub c_naming: bool,
+    pub alias_defines: bool,
+    pub allow_dead_code: bool,
+    pub allow_dead_code_per_item: bool,
+    pub float16_as_f16: bool,
+    pub trim_prefix: String,
+    pub trim_prefix_reexport: bool,
+    pub slice_accessors: Vec<(String, String, String)>,
+    pub emit_generated_hash: bool,
+    pub generate_block_comments: bool,
+    pub raw_link_names: bool,
+    pub blocklist_item: Vec<String>,
+    pub emit_original_name_comment: bool,
+    pub constified_enum_newtype: Vec<String>,
+    pub anon_field_prefix: String,
+    pub use_core_ffi: bool,
+    pub emit_crate_attributes: bool,
+    pub enum_with_unknown: Vec<String>,
+    pub volatile_accessors: bool,
+    pub helpers_module: Option<String>,
+    pub enum_discriminant_type_suffix: bool,
+    pub functions: bool,
+    pub enums: bool,
+    pub globals: bool,
+    pub types: bool,
+    pub record_matched_files: bool,
+    pub whitelist_types: Vec<String>,
+    pub whitelist_functions: Vec<String>,
+    pub whitelist_vars: Vec<String>,
+    pub bitfield_enums: Vec<String>,
+    pub derives: Vec<String>,
+    pub strip_comments: bool,
+    pub generate_comments: bool,
+    pub fixed_width_integers: bool,
+    pub emit_layout_constants: bool,
+    pub opaque_types: Vec<String>,
+    pub generate_macro_constants: bool,
+    pub emit_function_attributes: bool,
+    pub link_prefix: String,
+    pub strip_prefix: String,
+    pub maybe_uninit_fields: bool,
+    pub headers: Vec<String>,
+    pub emit_base_deref: bool,
+    pub flag_constant_types: Vec<(String, String)>,
+    pub pch: Option<String>,
+    pub cstr_fields: Vec<String>,
+    pub raw_lines: Vec<(Option<String>, String)>,
+    pub features: Vec<String>,
+    pub emit_prelude: bool,
+    pub function_prefix_modules: Vec<(String, String)>,
+    pub array_newtype_indexing: bool,
+    pub union_access_by_value: bool,
+    pub recursive_allowlist_report: bool,
+    pub cxx_methods: bool,
+    pub manual_hash_floats: bool,
+    pub emit_doc_alias: bool,
+    pub initializers: Vec<(String, String)>,
+    pub emit_track_caller: bool,
 }
 
 impl Default for BindgenOptions {
@@ -142,6 +767,10 @@ impl Default for BindgenOptions {
             args.push("-idirafter".to_owned());
             args.push(dir.to_str().unwrap().to_owned());
         }
+        if let Some(dir) = clang_resource_dir(&clang.path) {
+            args.push("-resource-dir".to_owned());
+            args.push(dir);
+        }
         BindgenOptions {
             match_pat: Vec::new(),
             builtins: false,
@@ -152,6 +781,74 @@ impl Default for BindgenOptions {
             override_enum_ty: "".to_owned(),
             clang_args: args,
             derive_debug: true,
+            wchar_as_u32: false,
+            sort_functions_by_name: false,
+            pod_byte_helpers: Vec::new(),
+            dynamic_library_runtime: false,
+            no_copy: Vec::new(),
+            treat_warnings_as_errors: false,
+            emit_clang_diagnostics: true,
+            layout_offset_tests: false,
+            field_visibility: Visibility::Public,
+            field_visibility_overrides: Vec::new(),
+            c_naming: false,
+            alias_defines: false,
+            allow_dead_code: true,
+            allow_dead_code_per_item: false,
+            float16_as_f16: false,
+            trim_prefix: "".to_owned(),
+            trim_prefix_reexport: false,
+            slice_accessors: Vec::new(),
+            emit_generated_hash: false,
+            generate_block_comments: false,
+            raw_link_names: false,
+            blocklist_item: Vec::new(),
+            emit_original_name_comment: false,
+            constified_enum_newtype: Vec::new(),
+            anon_field_prefix: "_bindgen_data_".to_owned(),
+            use_core_ffi: false,
+            emit_crate_attributes: false,
+            enum_with_unknown: Vec::new(),
+            volatile_accessors: false,
+            helpers_module: None,
+            enum_discriminant_type_suffix: false,
+            functions: true,
+            enums: true,
+            globals: true,
+            types: true,
+            record_matched_files: false,
+            whitelist_types: Vec::new(),
+            whitelist_functions: Vec::new(),
+            whitelist_vars: Vec::new(),
+            bitfield_enums: Vec::new(),
+            derives: Vec::new(),
+            strip_comments: false,
+            generate_comments: false,
+            fixed_width_integers: false,
+            emit_layout_constants: false,
+            opaque_types: Vec::new(),
+            generate_macro_constants: false,
+            emit_function_attributes: false,
+            link_prefix: "".to_owned(),
+            strip_prefix: "".to_owned(),
+            maybe_uninit_fields: false,
+            headers: vec![],
+            emit_base_deref: false,
+            flag_constant_types: Vec::new(),
+            pch: None,
+            cstr_fields: Vec::new(),
+            raw_lines: Vec::new(),
+            features: Vec::new(),
+            emit_prelude: false,
+            function_prefix_modules: Vec::new(),
+            array_newtype_indexing: false,
+            union_access_by_value: false,
+            recursive_allowlist_report: false,
+            cxx_methods: false,
+            manual_hash_floats: false,
+            emit_doc_alias: false,
+            initializers: Vec::new(),
+            emit_track_caller: false,
         }
     }
 }
@@ -163,15 +860,83 @@ pub enum LinkType {
     Framework,
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Visibility {
+    Public,
+    PublicCrate,
+    Private,
+}
+
 pub trait Logger: std::fmt::Debug {
     fn error(&self, msg: &str);
     fn warn(&self, msg: &str);
 }
 
+/// The reasons the bindings could not be generated.
+#[derive(Debug)]
+pub enum BindgenError {
+    /// Clang could not be found or loaded.
+    ClangNotFound,
+    /// The headers could not be parsed, with the error messages.
+    ParseFailed(String),
+    /// The headers reached an `#error` directive, with its message.
+    ErrorDirective(String),
+    /// An unsupported type was hit with `Builder::forbid_unknown_types`.
+    UnknownType(String),
+    /// Options that cannot be used together were given, with the reason.
+    InvalidOptions(String),
+    /// A file could not be read or written.
+    Io(io::Error),
+}
+
+impl fmt::Display for BindgenError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BindgenError::ClangNotFound => write!(f, "clang could not be loaded"),
+            BindgenError::ParseFailed(ref msg) => write!(f, "failed to parse the headers: {}", msg),
+            BindgenError::ErrorDirective(ref msg) => write!(f, "#error {}", msg),
+            BindgenError::UnknownType(ref msg) => write!(f, "unknown type: {}", msg),
+            BindgenError::InvalidOptions(ref msg) => write!(f, "invalid options: {}", msg),
+            BindgenError::Io(ref e) => write!(f, "I/O error: {}", e),
+        }
+    }
+}
+
+impl error::Error for BindgenError {
+    fn description(&self) -> &str {
+        match *self {
+            BindgenError::ClangNotFound => "clang could not be loaded",
+            BindgenError::ParseFailed(_) => "failed to parse the headers",
+            BindgenError::ErrorDirective(_) => "the headers reached an #error directive",
+            BindgenError::UnknownType(_) => "unknown type",
+            BindgenError::InvalidOptions(_) => "invalid options",
+            BindgenError::Io(ref e) => e.description(),
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            BindgenError::Io(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BindgenError {
+    fn from(e: io::Error) -> BindgenError {
+        BindgenError::Io(e)
+    }
+}
+
 #[derive(Clone)]
 pub struct Bindings {
     module: ast::Mod,
     attributes: Vec<ast::Attribute>,
+    input_hash: Option<u64>,
+    block_comments: bool,
+    source_files: Vec<String>,
+    links: Vec<(String, LinkType)>,
+    raw_lines: Vec<String>,
 }
 
 impl Bindings {
@@ -180,7 +945,7 @@ impl Bindings {
     pub fn generate(options: &BindgenOptions,
                     logger: Option<&Logger>,
                     span: Option<Span>)
-                    -> Result<Bindings, ()> {
+                    -> Result<Bindings, BindgenError> {
         let l = DummyLogger;
         let logger = match logger {
             Some(l) => l,
@@ -192,20 +957,74 @@ impl Bindings {
             None => DUMMY_SP,
         };
 
-        let globals = try!(parse_headers(options, logger));
+        let logger = CountingLogger::new(logger);
+
+        if options.dynamic_library_runtime && options.use_core_ffi {
+            let msg = "`dynamic_library_runtime` needs `std`, it cannot be used with `use_core_ffi`";
+            logger.error(msg);
+            return Err(BindgenError::InvalidOptions(msg.to_owned()));
+        }
+
+        let patterns = try!(gen::NamePatterns::new(options, &logger));
+        let stdin = try!(read_stdin(options));
+        let (globals, global_files) = try!(parse_headers(options, stdin.clone(), &logger));
+        let source_files = gen::emitted_files(options, &patterns, &globals, &global_files);
 
-        let (m, attrs) = gen::gen_mod(options, globals, span);
+        let (m, attrs) = gen::gen_mod(options, patterns, globals, &logger, span);
+
+        if options.treat_warnings_as_errors && logger.warnings.get() > 0 {
+            let msg = format!("{} warning(s) treated as errors", logger.warnings.get());
+            logger.error(&msg);
+            return Err(BindgenError::ParseFailed(msg));
+        }
         let module = ast::Mod {
             inner: span,
             items: m,
         };
 
+        let input_hash = if options.emit_generated_hash {
+            Some(input_hash(options, &stdin))
+        } else {
+            None
+        };
+
         Ok(Bindings {
             module: module,
             attributes: attrs,
+            input_hash: input_hash,
+            block_comments: options.generate_block_comments && !options.strip_comments,
+            source_files: source_files,
+            links: options.links.clone(),
+            raw_lines: options.raw_lines
+                              .iter()
+                              .filter(|&&(ref feature, _)| {
+                                  feature.as_ref().map_or(true, |f| options.features.contains(f))
+                              })
+                              .map(|&(_, ref line)| line.clone())
+                              .collect(),
         })
     }
 
+    /// The files the generated items were declared in, recorded with
+    /// `Builder::record_matched_files`.
+    pub fn source_files(&self) -> Vec<String> {
+        self.source_files.clone()
+    }
+
+    /// Write a `cargo:rustc-link-lib` line for each library to link, for the
+    /// build scripts.
+    pub fn write_cargo_link_directives<W: Write>(&self, mut out: W) -> io::Result<()> {
+        for &(ref name, kind) in &self.links {
+            let kind = match kind {
+                LinkType::Static => "static",
+                LinkType::Dynamic => "dylib",
+                LinkType::Framework => "framework",
+            };
+            try!(writeln!(out, "cargo:rustc-link-lib={}={}", kind, name));
+        }
+        Ok(())
+    }
+
     pub fn into_ast(self) -> Vec<P<ast::Item>> {
         self.module.items
     }
@@ -224,18 +1043,154 @@ impl Bindings {
         self.write(Box::new(file))
     }
 
+    /// Insert the bindings in the file at `path` before the `// BINDGEN-END`
+    /// line, or at its end when there is no such line, keeping the rest of
+    /// the file. The crate attributes like `#![allow(...)]` are left out, as
+    /// they can't follow the items of the file.
+    pub fn append_to_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut contents = String::new();
+        match File::open(&path) {
+            Ok(mut file) => {
+                try!(file.read_to_string(&mut contents));
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e),
+        }
+
+        let at = contents.find(APPEND_SENTINEL).unwrap_or(contents.len());
+        let (before, after) = contents.split_at(at);
+        let mut file = try!(OpenOptions::new().write(true).truncate(true).create(true).open(path));
+        try!(file.write_all(before.as_bytes()));
+        if !before.is_empty() && !before.ends_with('\n') {
+            try!(file.write_all(b"\n"));
+        }
+        let mut items = Vec::new();
+        {
+            let ref_writer = Box::new(items.by_ref()) as Box<Write>;
+            try!(self.write_with_attributes(ref_writer, &[]));
+        }
+        try!(file.write_all(&items));
+        file.write_all(after.as_bytes())
+    }
+
     // https://github.com/Manishearth/rust-clippy/issues/740
     #[cfg_attr(feature = "clippy", allow(needless_lifetimes))]
-    pub fn write<'a>(&self, mut writer: Box<Write + 'a>) -> io::Result<()> {
-        try!(writer.write("/* automatically generated by rust-bindgen */\n\n".as_bytes()));
+    pub fn write<'a>(&self, writer: Box<Write + 'a>) -> io::Result<()> {
+        self.write_with_attributes(writer, &self.attributes)
+    }
+
+    /// Writes the bindings with the given inner attributes.
+    #[cfg_attr(feature = "clippy", allow(needless_lifetimes))]
+    fn write_with_attributes<'a>(&self,
+                                 mut writer: Box<Write + 'a>,
+                                 attributes: &[ast::Attribute])
+                                 -> io::Result<()> {
+        try!(writer.write("/* automatically generated by rust-bindgen */\n".as_bytes()));
+        if let Some(hash) = self.input_hash {
+            try!(writer.write(format!("/* bindgen input hash: {:016x} */\n", hash).as_bytes()));
+        }
+        try!(writer.write("\n".as_bytes()));
         let mut ps = pprust::rust_printer(writer);
-        try!(ps.print_mod(&self.module, &self.attributes));
+        // The inner attributes have to come first, before the raw lines.
+        let attrs_only = ast::Mod {
+            inner: self.module.inner,
+            items: vec![],
+        };
+        try!(ps.print_mod(&attrs_only, attributes));
+        for line in &self.raw_lines {
+            try!(pp::word(&mut ps.s, line));
+            try!(pp::hardbreak(&mut ps.s));
+        }
+        if !self.raw_lines.is_empty() {
+            try!(pp::hardbreak(&mut ps.s));
+        }
+        if self.block_comments {
+            let mut current = None;
+            for item in &self.module.items {
+                let section = item_section(item);
+                if section.is_some() && section != current {
+                    try!(pp::hardbreak(&mut ps.s));
+                    try!(pp::word(&mut ps.s, &format!("// --- {} ---", section.unwrap())));
+                    try!(pp::hardbreak(&mut ps.s));
+                    current = section;
+                }
+                try!(ps.print_item(item));
+            }
+        } else {
+            try!(ps.print_mod(&self.module, &[]));
+        }
         try!(ps.print_remaining_comments());
         try!(eof(&mut ps.s));
         ps.s.out.flush()
     }
 }
 
+/// The line before which `Bindings::append_to_file` inserts the bindings.
+const APPEND_SENTINEL: &'static str = "// BINDGEN-END";
+
+/// The section banner to write before `item`, `None` for the items belonging to
+/// the current section, like the implementations.
+fn item_section(item: &ast::Item) -> Option<&'static str> {
+    match item.node {
+        ast::ItemKind::Struct(..) |
+        ast::ItemKind::Enum(..) |
+        ast::ItemKind::Ty(..) => Some("types"),
+        ast::ItemKind::Const(..) |
+        ast::ItemKind::Static(..) => Some("constants"),
+        ast::ItemKind::ForeignMod(ref foreign) => {
+            let all_statics = foreign.items.iter().all(|i| {
+                match i.node {
+                    ast::ForeignItemKind::Static(..) => true,
+                    _ => false,
+                }
+            });
+            if all_statics {
+                Some("variables")
+            } else {
+                Some("functions")
+            }
+        }
+        ast::ItemKind::Use(..) => Some("re-exports"),
+        _ => None,
+    }
+}
+
+/// Reads the standard input when it is among the headers, once for the parser
+/// and the input hash.
+fn read_stdin(options: &BindgenOptions) -> Result<Option<String>, BindgenError> {
+    if !options.headers.iter().any(|h| h == "-") {
+        return Ok(None);
+    }
+    let mut contents = String::new();
+    try!(io::stdin().read_to_string(&mut contents).map_err(BindgenError::Io));
+    Ok(Some(contents))
+}
+
+/// Hashes the options and the contents of the files given to clang. FNV-1a is
+/// used so that the hash doesn't change with the Rust version.
+fn input_hash(options: &BindgenOptions, stdin: &Option<String>) -> u64 {
+    fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        hash
+    }
+
+    let mut hash = fnv1a(0xcbf29ce484222325, format!("{:?}", options).as_bytes());
+    for arg in options.headers.iter().chain(options.clang_args.iter()) {
+        let mut contents = vec![];
+        if let Ok(mut file) = File::open(arg) {
+            if file.read_to_end(&mut contents).is_ok() {
+                hash = fnv1a(hash, &contents);
+            }
+        }
+    }
+    if let Some(ref contents) = *stdin {
+        hash = fnv1a(hash, contents.as_bytes());
+    }
+    hash
+}
 
 #[derive(Debug)]
 struct DummyLogger;
@@ -245,7 +1200,37 @@ impl Logger for DummyLogger {
     fn warn(&self, _msg: &str) {}
 }
 
-fn parse_headers(options: &BindgenOptions, logger: &Logger) -> Result<Vec<Global>, ()> {
+/// Forwards to another logger, counting the warnings.
+#[derive(Debug)]
+struct CountingLogger<'a> {
+    logger: &'a Logger,
+    warnings: Cell<usize>,
+}
+
+impl<'a> CountingLogger<'a> {
+    fn new(logger: &'a Logger) -> CountingLogger<'a> {
+        CountingLogger {
+            logger: logger,
+            warnings: Cell::new(0),
+        }
+    }
+}
+
+impl<'a> Logger for CountingLogger<'a> {
+    fn error(&self, msg: &str) {
+        self.logger.error(msg)
+    }
+
+    fn warn(&self, msg: &str) {
+        self.warnings.set(self.warnings.get() + 1);
+        self.logger.warn(msg)
+    }
+}
+
+fn parse_headers(options: &BindgenOptions,
+                 stdin: Option<String>,
+                 logger: &Logger)
+                 -> Result<(Vec<Global>, Vec<String>), BindgenError> {
     fn str_to_ikind(s: &str) -> Option<types::IKind> {
         match s {
             "uchar" => Some(types::IUChar),
@@ -262,6 +1247,12 @@ fn parse_headers(options: &BindgenOptions, logger: &Logger) -> Result<Vec<Global
         }
     }
 
+    let mut clang_args = options.clang_args.clone();
+    if let Some(ref pch) = options.pch {
+        clang_args.insert(0, pch.clone());
+        clang_args.insert(0, "-include-pch".to_owned());
+    }
+
     let clang_opts = parser::ClangParserOptions {
         builtin_names: builtin_names(),
         builtins: options.builtins,
@@ -269,12 +1260,42 @@ fn parse_headers(options: &BindgenOptions, logger: &Logger) -> Result<Vec<Global
         emit_ast: options.emit_ast,
         fail_on_unknown_type: options.fail_on_unknown_type,
         override_enum_ty: str_to_ikind(&options.override_enum_ty[..]),
-        clang_args: options.clang_args.clone(),
+        clang_args: clang_args,
+        headers: options.headers.clone(),
+        stdin: stdin,
+        wchar_as_u32: options.wchar_as_u32,
+        emit_clang_diagnostics: options.emit_clang_diagnostics,
+        alias_defines: options.alias_defines,
+        record_matched_files: options.record_matched_files,
+        whitelist_types: options.whitelist_types.clone(),
+        whitelist_functions: options.whitelist_functions.clone(),
+        whitelist_vars: options.whitelist_vars.clone(),
+        opaque_types: options.opaque_types.clone(),
+        macro_constants: options.generate_macro_constants,
+        allowlist_report: options.recursive_allowlist_report,
+        cxx_methods: options.cxx_methods,
     };
 
     parser::parse(clang_opts, logger)
 }
 
+/// Ask the clang binary for its resource directory, where the builtin headers
+/// such as `stddef.h` live.
+fn clang_resource_dir(clang: &Path) -> Option<String> {
+    let output = match Command::new(clang).arg("-print-resource-dir").output() {
+        Ok(output) => output,
+        Err(_) => return None,
+    };
+    if !output.status.success() {
+        return None;
+    }
+    let dir = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    if dir.is_empty() || !Path::new(&dir).is_dir() {
+        return None;
+    }
+    Some(dir)
+}
+
 fn builtin_names() -> HashSet<String> {
     let mut names = HashSet::new();
     let keys = ["__va_list_tag", "__va_list", "__builtin_va_list"];
@@ -296,6 +1317,51 @@ fn builder_state() {
         build.log(&logger);
     }
     assert!(build.logger.is_some());
-    assert!(build.options.clang_args.binary_search(&"example.h".to_owned()).is_ok());
+    assert!(build.options.headers.binary_search(&"example.h".to_owned()).is_ok());
     assert!(build.options.links.binary_search(&("m".to_owned(), LinkType::Static)).is_ok());
 }
+
+#[test]
+fn clang_resource_dir_override() {
+    let mut build = builder();
+    build.clang_resource_dir("/opt/clang/lib/clang/3.8");
+    build.clang_resource_dir("/usr/lib/clang/3.9");
+    let args = &build.options.clang_args;
+    assert_eq!(args.iter().filter(|a| *a == "-resource-dir").count(), 1);
+    let i = args.iter().position(|a| a == "-resource-dir").unwrap();
+    assert_eq!(args[i + 1], "/usr/lib/clang/3.9");
+}
+
+#[test]
+fn default_clang_resource_dir() {
+    let clang = Clang::find(None).unwrap();
+    let dir = match clang_resource_dir(&clang.path) {
+        Some(dir) => dir,
+        // `clang -print-resource-dir` failed, there is nothing to detect.
+        None => return,
+    };
+    let args = BindgenOptions::default().clang_args;
+    let i = args.iter().position(|a| a == "-resource-dir").unwrap();
+    assert_eq!(args[i + 1], dir);
+}
+
+#[test]
+fn target_env_args() {
+    let mut build = builder();
+    build.for_wasm32();
+    let args = &build.options.clang_args;
+    let i = args.iter().position(|a| a == "-target").unwrap();
+    assert_eq!(args[i + 1], "wasm32-unknown-unknown");
+    assert!(build.options.fixed_width_integers);
+}
+
+#[test]
+fn target_env_override() {
+    let mut build = builder();
+    build.for_wasm32();
+    build.for_aarch64_linux();
+    let args = &build.options.clang_args;
+    assert_eq!(args.iter().filter(|a| *a == "-target").count(), 1);
+    let i = args.iter().position(|a| a == "-target").unwrap();
+    assert_eq!(args[i + 1], "aarch64-unknown-linux-gnu");
+}
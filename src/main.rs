@@ -27,9 +27,11 @@ const USAGE: &'static str = "
 Generate C bindings for Rust.
 
 Usage:
-  bindgen [options] <file>
+  bindgen [options] <file>...
   bindgen (-h | --help)
 
+The headers are parsed in order into the same bindings, - reads one from stdin.
+
 Options:
   -h, --help                   Display this help message.
   --link=<library>             Link to a dynamic library, can be provided multiple times.
@@ -56,11 +58,22 @@ Options:
                                  ulonglong
                                  slonglong
   --clang-options=<opts>      Options to clang.
+  --no-functions               Don't output bindings for the functions.
+  --no-enums                   Don't output bindings for the enums.
+  --no-globals                 Don't output bindings for the global variables.
+  --no-types                   Don't output bindings for the structs, unions
+                               and typedefs.
+  --whitelist-type=<regex>     Only output bindings for the types whose name
+                               matches <regex>, and the types they depend on.
+  --whitelist-function=<regex> Only output bindings for the functions whose
+                               name matches <regex>.
+  --whitelist-var=<regex>      Only output bindings for the variables whose
+                               name matches <regex>.
 ";
 
 #[derive(Debug, RustcDecodable)]
 struct Args {
-    arg_file: String,
+    arg_file: Vec<String>,
     flag_link: String,
     flag_output: String,
     flag_match: Option<String>,
@@ -68,19 +81,41 @@ struct Args {
     flag_emit_clang_ast: bool,
     flag_override_enum_type: String,
     flag_clang_options: String,
+    flag_no_functions: bool,
+    flag_no_enums: bool,
+    flag_no_globals: bool,
+    flag_no_types: bool,
+    flag_whitelist_type: Option<String>,
+    flag_whitelist_function: Option<String>,
+    flag_whitelist_var: Option<String>,
 }
 
 fn args_to_opts(args: Args, builder: &mut Builder) {
-    builder.header(args.arg_file)
-           .emit_ast(args.flag_emit_clang_ast)
+    for file in args.arg_file {
+        builder.header(file);
+    }
+    builder.emit_ast(args.flag_emit_clang_ast)
            .override_enum_ty(args.flag_override_enum_type)
-           .clang_arg(args.flag_clang_options);
+           .clang_arg(args.flag_clang_options)
+           .emit_functions(!args.flag_no_functions)
+           .emit_enums(!args.flag_no_enums)
+           .emit_globals(!args.flag_no_globals)
+           .emit_types(!args.flag_no_types);
     if let Some(s) = args.flag_match {
         builder.match_pat(s);
     }
     if args.flag_builtins {
         builder.builtins();
     }
+    if let Some(s) = args.flag_whitelist_type {
+        builder.whitelist_type(s);
+    }
+    if let Some(s) = args.flag_whitelist_function {
+        builder.whitelist_function(s);
+    }
+    if let Some(s) = args.flag_whitelist_var {
+        builder.whitelist_var(s);
+    }
     let mut parts = args.flag_link.split('=');
     let (lib, kind) = match (parts.next(), parts.next()) {
         (Some(lib), None) => (lib, LinkType::Dynamic),
@@ -136,6 +171,9 @@ pub fn main() {
                 }
             }
         }
-        Err(()) => exit(-1),
+        Err(e) => {
+            let _ = writeln!(io::stderr(), "{}", e);
+            exit(-1);
+        }
     }
 }
@@ -2,20 +2,24 @@
 
 use std::collections::{HashMap, HashSet};
 use std::collections::hash_map;
+use std::mem;
 use std::cell::RefCell;
 use std::ops::Deref;
+use std::path::Path;
 use std::rc::Rc;
 
 use clang_sys::*;
 
+use regex::RegexSet;
+
 use syntax::abi;
 
 use types as il;
 use types::*;
 use clang as cx;
-use clang::{Cursor, Diagnostic, TranslationUnit, ast_dump};
+use clang::{Cursor, Diagnostic, TranslationUnit, UnsavedFile, ast_dump};
 
-use super::Logger;
+use super::{BindgenError, Logger};
 
 pub struct ClangParserOptions {
     pub builtin_names: HashSet<String>,
@@ -25,6 +29,22 @@ pub struct ClangParserOptions {
     pub fail_on_unknown_type: bool,
     pub override_enum_ty: Option<il::IKind>,
     pub clang_args: Vec<String>,
+    /// The headers to parse in order, `-` being read from the standard input.
+    /// Without any, the header is expected among the clang arguments.
+    pub headers: Vec<String>,
+    /// The contents of the standard input, read when it is among the headers.
+    pub stdin: Option<String>,
+    pub wchar_as_u32: bool,
+    pub emit_clang_diagnostics: bool,
+    pub alias_defines: bool,
+    pub record_matched_files: bool,
+    pub whitelist_types: Vec<String>,
+    pub whitelist_functions: Vec<String>,
+    pub whitelist_vars: Vec<String>,
+    pub opaque_types: Vec<String>,
+    pub macro_constants: bool,
+    pub allowlist_report: bool,
+    pub cxx_methods: bool,
 }
 
 struct ClangParserCtx<'a> {
@@ -33,7 +53,17 @@ struct ClangParserCtx<'a> {
     globals: Vec<Global>,
     builtin_defs: Vec<Cursor>,
     logger: &'a (Logger + 'a),
-    err_count: i32,
+    /// The errors reported so far, which make the parsing fail.
+    errors: Vec<String>,
+    /// The unsupported types hit with `fail_on_unknown_type`.
+    unknown_types: Vec<String>,
+    /// The messages of the `#error` directives reached.
+    error_directives: Vec<String>,
+    /// The file of each of the globals, when recorded.
+    global_files: Vec<String>,
+    opaque_types: Option<RegexSet>,
+    /// Whether `wchar_t` is signed on the target, once asked to clang.
+    wchar_signed: Option<bool>,
 }
 
 fn match_pattern(ctx: &mut ClangParserCtx, cursor: &Cursor) -> bool {
@@ -44,11 +74,7 @@ fn match_pattern(ctx: &mut ClangParserCtx, cursor: &Cursor) -> bool {
         Some(name) => name,
     };
 
-    if ctx.options.match_pat.is_empty() {
-        return true;
-    }
-
-    let mut found = false;
+    let mut found = ctx.options.match_pat.is_empty();
     ctx.options.match_pat.iter().all(|pat| {
         if (&name[..]).contains(pat) {
             found = true;
@@ -126,6 +152,15 @@ fn decl_name(ctx: &mut ClangParserCtx, cursor: &Cursor) -> Global {
                 _ => GOther,
             };
 
+            let comment = doc_comment(&cursor.raw_comment());
+            match glob_decl {
+                GCompDecl(ref ci) => ci.borrow_mut().comment = comment,
+                GEnumDecl(ref ei) => ei.borrow_mut().comment = comment,
+                GType(ref ti) => ti.borrow_mut().comment = comment,
+                GVar(ref vi) | GFunc(ref vi) => vi.borrow_mut().comment = comment,
+                _ => {}
+            }
+
             e.insert(glob_decl.clone());
             glob_decl
         }
@@ -219,6 +254,15 @@ fn mk_fn_sig(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Fu
         }
     };
 
+    // K&R definitions have no prototype, but declare their parameters. They are
+    // not variadic, and take their arguments after the default promotions.
+    let is_knr_definition = ty.kind() == CXTypeKind::FunctionNoProto && !args_lst.is_empty();
+    let args_lst = if is_knr_definition {
+        args_lst.into_iter().map(|(name, ty)| (name, promote_knr_arg(ty))).collect()
+    } else {
+        args_lst
+    };
+
     let ret_ty = Box::new(conv_ty(ctx, &ty.ret_type(), cursor));
     let abi = get_abi(ty.call_conv());
 
@@ -233,12 +277,25 @@ fn mk_fn_sig(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Fu
     il::FuncSig {
         ret_ty: ret_ty,
         args: args_lst,
-        is_variadic: ty.is_variadic(),
+        is_variadic: ty.is_variadic() && !is_knr_definition,
         is_safe: !is_unsafe,
         abi: abi,
     }
 }
 
+/// Applies the default argument promotions to the type of a K&R parameter.
+fn promote_knr_arg(ty: il::Type) -> il::Type {
+    match ty {
+        TInt(IBool, _) |
+        TInt(ISChar, _) |
+        TInt(IUChar, _) |
+        TInt(IShort, _) |
+        TInt(IUShort, _) => TInt(IInt, Layout::new(4, 4)),
+        TFloat(FFloat, _) => TFloat(FDouble, Layout::new(8, 8)),
+        ty => ty,
+    }
+}
+
 fn conv_decl_ty(ctx: &mut ClangParserCtx, cursor: &Cursor) -> il::Type {
     match cursor.kind() {
         CXCursorKind::StructDecl | CXCursorKind::UnionDecl => {
@@ -270,6 +327,12 @@ fn conv_ty(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Type
 
     let layout = Layout::new(ty.size(), ty.align());
 
+    // There is no `Float16` type kind in clang-sys, so look at the spelling.
+    match &ty.name()[..] {
+        "_Float16" | "const _Float16" => return TFloat(FFloat16, layout),
+        _ => {}
+    }
+
     match ty.kind() {
         CXTypeKind::Void | CXTypeKind::Invalid => TVoid,
         CXTypeKind::Bool => TInt(IBool, layout),
@@ -285,6 +348,15 @@ fn conv_ty(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Type
         CXTypeKind::Int => TInt(IInt, layout),
         CXTypeKind::Long => TInt(ILong, layout),
         CXTypeKind::LongLong => TInt(ILongLong, layout),
+        CXTypeKind::WChar => {
+            if ctx.options.wchar_as_u32 && layout.size == 4 {
+                TInt(IChar32, layout)
+            } else {
+                TInt(IWChar(wchar_is_signed(ctx)), layout)
+            }
+        }
+        CXTypeKind::Char16 => TInt(IChar16, layout),
+        CXTypeKind::Char32 => TInt(IChar32, layout),
         CXTypeKind::Float => TFloat(FFloat, layout),
         CXTypeKind::Double | CXTypeKind::LongDouble => TFloat(FDouble, layout),
         CXTypeKind::Pointer => conv_ptr_ty(ctx, &ty.pointee_type(), cursor, layout),
@@ -295,9 +367,20 @@ fn conv_ty(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Type
         CXTypeKind::FunctionProto | CXTypeKind::FunctionNoProto => {
             TFuncProto(mk_fn_sig(ctx, ty, cursor), layout)
         }
+        CXTypeKind::Unexposed => {
+            // `typeof` and `decltype` have no declaration, use the type they
+            // stand for.
+            let decl = ty.declaration();
+            let canonical = ty.canonical_type();
+            if decl.kind() == CXCursorKind::NoDeclFound &&
+               canonical.kind() != CXTypeKind::Unexposed {
+                conv_ty(ctx, &canonical, cursor)
+            } else {
+                conv_decl_ty(ctx, &decl)
+            }
+        }
         CXTypeKind::Record |
         CXTypeKind::Typedef |
-        CXTypeKind::Unexposed |
         CXTypeKind::Enum => conv_decl_ty(ctx, &ty.declaration()),
         CXTypeKind::ConstantArray => {
             TArray(Box::new(conv_ty(ctx, &ty.elem_type(), cursor)),
@@ -306,17 +389,42 @@ fn conv_ty(ctx: &mut ClangParserCtx, ty: &cx::Type, cursor: &Cursor) -> il::Type
         }
         _ => {
             let fail = ctx.options.fail_on_unknown_type;
-            log_err_warn(ctx,
-                         &format!("unsupported type `{:?}` ({})",
-                                  ty.kind(),
-                                  cursor.location())[..],
-                         fail);
+            let msg = format!("unsupported type `{:?}` ({})", ty.kind(), cursor.location());
+            log_err_warn(ctx, &msg[..], fail);
+            if fail {
+                ctx.unknown_types.push(msg);
+            }
             TVoid
         }
     }
 }
 
+/// Whether the declaration is an instantiation or a specialization of a C++
+/// class template.
+fn is_template_instance(decl: &Cursor) -> bool {
+    match decl.specialized_template().kind() {
+        CXCursorKind::ClassTemplate | CXCursorKind::ClassTemplatePartialSpecialization => true,
+        _ => false,
+    }
+}
+
+/// An instantiation of a class template, as an unnamed opaque struct with the
+/// size clang reports, named after the typedef by the generator. When clang
+/// can't tell the size, as the template isn't instantiated, the size is left
+/// at 0 and the struct is only declared.
+fn template_instance_ty(ty: &cx::Type) -> il::Type {
+    let layout = Layout::new(ty.size(), ty.align());
+    let mut ci = CompInfo::new("".to_owned(), CompKind::Struct, vec![], layout);
+    ci.opaque = true;
+    TComp(Rc::new(RefCell::new(ci)))
+}
+
 fn opaque_ty(ctx: &mut ClangParserCtx, ty: &cx::Type) {
+    // Handles such as `typedef struct foo_ *foo;` still need `foo_` declared,
+    // so that each handle points to its own opaque type.
+    if ty.kind() == CXTypeKind::Pointer {
+        return opaque_ty(ctx, &ty.pointee_type());
+    }
     if ty.kind() == CXTypeKind::Record || ty.kind() == CXTypeKind::Enum {
         let decl = ty.declaration();
         let def = decl.definition();
@@ -326,12 +434,50 @@ fn opaque_ty(ctx: &mut ClangParserCtx, ty: &cx::Type) {
     }
 }
 
+/// Whether `wchar_t` is signed on the target, which libclang doesn't expose.
+/// Clang is asked for the integer type `__WCHAR_TYPE__` stands for with the
+/// same arguments, `wchar_t` being taken as signed when that fails.
+fn wchar_is_signed(ctx: &mut ClangParserCtx) -> bool {
+    const WCHAR_HEADER: &'static str = "bindgen-wchar.h";
+
+    if let Some(signed) = ctx.wchar_signed {
+        return signed;
+    }
+
+    let mut signed = true;
+    let ix = cx::Index::create(false, false);
+    let unsaved = [UnsavedFile::new(WCHAR_HEADER, "typedef __WCHAR_TYPE__ bindgen_wchar;\n")];
+    let unit = TranslationUnit::parse(&ix,
+                                      WCHAR_HEADER,
+                                      &ctx.options.clang_args[..],
+                                      &unsaved[..],
+                                      CXTranslationUnit_Flags::empty());
+    if !unit.is_null() {
+        unit.cursor().visit(|c, _: &Cursor| {
+            if c.kind() == CXCursorKind::TypedefDecl && c.spelling() == "bindgen_wchar" {
+                signed = match c.typedef_type().canonical_type().kind() {
+                    CXTypeKind::UChar | CXTypeKind::Char_U | CXTypeKind::UShort |
+                    CXTypeKind::UInt | CXTypeKind::ULong => false,
+                    _ => true,
+                };
+            }
+            CXChildVisitResult::Continue
+        });
+        unit.dispose();
+    }
+    ix.dispose();
+
+    ctx.wchar_signed = Some(signed);
+    signed
+}
+
 /// Recursively visits a cursor that represents a composite (struct or union)
 /// type and fills members with `CompMember` instances representing the fields and
 /// nested composites that make up the visited composite.
 fn visit_composite(cursor: &Cursor,
                    parent: &Cursor,
                    ctx: &mut ClangParserCtx,
+                   unit: &TranslationUnit,
                    compinfo: &mut CompInfo)
                    -> CXChildVisitResult {
     fn is_bitfield_continuation(field: &il::FieldInfo, ty: &il::Type, width: u32) -> bool {
@@ -366,6 +512,11 @@ fn visit_composite(cursor: &Cursor,
         }
     }
 
+    if cursor.kind() == CXCursorKind::CXXBaseSpecifier {
+        visit_base(cursor, parent, ctx, compinfo);
+        return CXChildVisitResult::Continue;
+    }
+
     let members = &mut compinfo.members;
 
     match cursor.kind() {
@@ -454,7 +605,12 @@ fn visit_composite(cursor: &Cursor,
                 _ => false,
             };
 
-            let field = FieldInfo::new(name, ty.clone(), bitfields);
+            let mut field = FieldInfo::new(name, ty.clone(), bitfields);
+            if field.bitfields.is_none() && !field.name.is_empty() {
+                field.offset = parent.cur_type().offset_of(&field.name);
+            }
+            field.is_volatile = cursor.cur_type().is_volatile();
+            field.comment = doc_comment(&cursor.raw_comment());
             if is_composite {
                 if let Some(CompMember::Comp(c)) = members.pop() {
                     members.push(CompMember::CompField(c, field));
@@ -480,7 +636,7 @@ fn visit_composite(cursor: &Cursor,
                 let ci = decl.compinfo();
                 cursor.visit(|c, p| {
                     let mut ci_ = ci.borrow_mut();
-                    visit_composite(c, p, ctx_, &mut ci_)
+                    visit_composite(c, p, ctx_, unit, &mut ci_)
                 });
                 members.push(CompMember::Comp(decl.compinfo()));
             });
@@ -494,11 +650,34 @@ fn visit_composite(cursor: &Cursor,
                 let ci = decl.enuminfo();
                 cursor.visit(|c, _| {
                     let mut ci_ = ci.borrow_mut();
-                    visit_enum(c, &mut ci_.items)
+                    let is_signed = ci_.kind.is_signed();
+                    visit_enum(c, is_signed, &mut ci_.items)
                 });
                 members.push(CompMember::Enum(decl.enuminfo()));
             });
         }
+        CXCursorKind::VarDecl => {
+            // C++ static members initialized in the class are constants, which
+            // get emitted like the constant globals, prefixed with the class.
+            if !cursor.cur_type().is_const() {
+                return CXChildVisitResult::Continue;
+            }
+            let mut val = None;
+            cursor.visit(|c, _: &Cursor| {
+                val = visit_literal(c, unit);
+                CXChildVisitResult::Continue
+            });
+            if let Some(val) = val {
+                let name = format!("{}_{}", compinfo.name, cursor.spelling());
+                let mut vi = VarInfo::new(name, conv_ty(ctx, &cursor.cur_type(), cursor));
+                vi.is_const = true;
+                vi.val = Some(val);
+                ctx.globals.push(GVar(Rc::new(RefCell::new(vi))));
+            }
+        }
+        CXCursorKind::CXXMethod if ctx.options.cxx_methods => {
+            visit_method(cursor, parent, ctx, compinfo);
+        }
         CXCursorKind::PackedAttr => {
             compinfo.layout.packed = true;
         }
@@ -520,11 +699,99 @@ fn visit_composite(cursor: &Cursor,
     CXChildVisitResult::Continue
 }
 
-fn visit_enum(cursor: &Cursor, items: &mut Vec<EnumItem>) -> CXChildVisitResult {
+/// Declares a C++ method as a function named after its class, taking the
+/// object as `this`, a `*const` pointer for the `const` methods.
+fn visit_method(cursor: &Cursor, parent: &Cursor, ctx: &mut ClangParserCtx, compinfo: &CompInfo) {
+    if cursor.is_pure_virtual_method() {
+        return;
+    }
+
+    let ty = cursor.cur_type();
+    let layout = Layout::new(ty.size(), ty.align());
+    let mut sig = mk_fn_sig(ctx, &ty, cursor);
+    if !cursor.is_static_method() {
+        let class = TComp(decl_name(ctx, parent).compinfo());
+        let this = TPtr(Box::new(class), cursor.is_const_method(), Layout::default());
+        sig.args.insert(0, ("this".to_owned(), this));
+        sig.is_safe = false;
+    }
+
+    // The overloads are numbered after the first one.
+    let base_name = format!("{}_{}", compinfo.name, cursor.spelling());
+    let mut name = base_name.clone();
+    let mut overload = 0;
+    while ctx.globals.iter().any(|g| {
+        match *g {
+            GFunc(ref vi) => vi.borrow().name == name,
+            _ => false,
+        }
+    }) {
+        overload += 1;
+        name = format!("{}{}", base_name, overload);
+    }
+
+    // On macOS, clang gives the symbol with the `_` prefix of the platform,
+    // which rustc adds again to the link names.
+    let mut mangled = cursor.mangling();
+    if mangled.starts_with("__Z") {
+        mangled.remove(0);
+    }
+
+    let mut vi = VarInfo::new(name, TFuncPtr(sig, layout));
+    vi.mangled = Some(mangled);
+    vi.comment = doc_comment(&cursor.raw_comment());
+    ctx.globals.push(GFunc(Rc::new(RefCell::new(vi))));
+}
+
+/// Embeds the single non-virtual base of a C++ class as its first field,
+/// `_base`. The classes with a virtual base or several bases are opaque.
+fn visit_base(cursor: &Cursor, parent: &Cursor, ctx: &mut ClangParserCtx, compinfo: &mut CompInfo) {
+    let ty = conv_ty(ctx, &cursor.cur_type(), cursor);
+    let has_base = compinfo.members.iter().any(|m| {
+        match *m {
+            CompMember::Field(ref f) => f.is_base,
+            _ => false,
+        }
+    });
+    if cursor.is_virtual_base() || has_base {
+        let msg = format!("`{}` has a virtual base or several bases, making it opaque",
+                          parent.spelling());
+        log_err_warn(ctx, &msg[..], false);
+        compinfo.opaque = true;
+        return;
+    }
+
+    // The empty bases take no room in the derived class.
+    let is_empty = match ty {
+        TComp(ref ci) => ci.borrow().members.is_empty(),
+        TNamed(ref ti) => {
+            match ti.borrow().ty {
+                TComp(ref ci) => ci.borrow().members.is_empty(),
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    if !is_empty {
+        let mut field = FieldInfo::new("_base".to_owned(), ty, None);
+        field.offset = Some(0);
+        field.is_base = true;
+        compinfo.members.push(CompMember::Field(field));
+    }
+}
+
+fn visit_enum(cursor: &Cursor, is_signed: bool, items: &mut Vec<EnumItem>) -> CXChildVisitResult {
     if cursor.kind() == CXCursorKind::EnumConstantDecl {
         let name = cursor.spelling();
-        let val = cursor.enum_val();
-        let item = EnumItem::new(name, val);
+        // The values of the unsigned enums past `i64::MAX` keep their bits,
+        // the generator reads them back as unsigned.
+        let val = if is_signed {
+            cursor.enum_val()
+        } else {
+            cursor.enum_unsigned_val() as i64
+        };
+        let mut item = EnumItem::new(name, val);
+        item.comment = doc_comment(&cursor.raw_comment());
         items.push(item);
     }
     CXChildVisitResult::Continue
@@ -559,6 +826,22 @@ fn visit_literal(cursor: &Cursor, unit: &TranslationUnit) -> Option<i64> {
     }
 }
 
+// Visits a top level cursor, recording the file of the globals it added.
+fn visit_top_recording(cursor: &Cursor,
+                       ctx: &mut ClangParserCtx,
+                       unit: &TranslationUnit)
+                       -> CXChildVisitResult {
+    let result = visit_top(cursor, ctx, unit);
+    if ctx.options.record_matched_files {
+        let (file, _, _, _) = cursor.location().location();
+        let name = file.name().unwrap_or(String::new());
+        while ctx.global_files.len() < ctx.globals.len() {
+            ctx.global_files.push(name.clone());
+        }
+    }
+    result
+}
+
 fn visit_top(cursor: &Cursor,
              ctx: &mut ClangParserCtx,
              unit: &TranslationUnit)
@@ -569,28 +852,29 @@ fn visit_top(cursor: &Cursor,
 
     match cursor.kind() {
         CXCursorKind::UnexposedDecl => CXChildVisitResult::Recurse,
+        CXCursorKind::StructDecl | CXCursorKind::UnionDecl if is_template_instance(cursor) => {
+            // The instantiations can't be represented, the typedefs naming
+            // them are opaque.
+            CXChildVisitResult::Continue
+        }
         CXCursorKind::StructDecl | CXCursorKind::UnionDecl => {
             fwd_decl(ctx, cursor, |ctx_| {
                 let decl = decl_name(ctx_, cursor);
                 let ci = decl.compinfo();
-                cursor.visit(|c, p| {
-                    let mut ci_ = ci.borrow_mut();
-                    visit_composite(c, p, ctx_, &mut ci_)
-                });
+                // The members of the opaque types are never translated, only
+                // their layout is used.
+                if !matches(&ctx_.opaque_types, &cursor.spelling()) {
+                    cursor.visit(|c, p| {
+                        let mut ci_ = ci.borrow_mut();
+                        visit_composite(c, p, ctx_, unit, &mut ci_)
+                    });
+                }
                 ctx_.globals.push(GComp(ci));
             });
             CXChildVisitResult::Continue
         }
         CXCursorKind::EnumDecl => {
-            fwd_decl(ctx, cursor, |ctx_| {
-                let decl = decl_name(ctx_, cursor);
-                let ei = decl.enuminfo();
-                cursor.visit(|c, _: &Cursor| {
-                    let mut ei_ = ei.borrow_mut();
-                    visit_enum(c, &mut ei_.items)
-                });
-                ctx_.globals.push(GEnum(ei));
-            });
+            visit_enum_decl(ctx, cursor);
             CXChildVisitResult::Continue
         }
         CXCursorKind::FunctionDecl => {
@@ -607,6 +891,17 @@ fn visit_top(cursor: &Cursor,
             let layout = Layout::new(ty.size(), ty.align());
 
             vi.ty = TFuncPtr(mk_fn_sig(ctx, &ty, cursor), layout);
+            cursor.visit(|c, _: &Cursor| {
+                match c.kind() {
+                    CXCursorKind::PureAttr => vi.is_pure = true,
+                    CXCursorKind::ConstAttr => vi.is_const_fn = true,
+                    CXCursorKind::UnexposedAttr if is_noreturn_attr(c, unit) => {
+                        vi.is_noreturn = true
+                    }
+                    _ => {}
+                }
+                CXChildVisitResult::Continue
+            });
             ctx.globals.push(func);
 
             CXChildVisitResult::Continue
@@ -623,6 +918,9 @@ fn visit_top(cursor: &Cursor,
             let mut vi = vi.borrow_mut();
             vi.ty = ty.clone();
             vi.is_const = cursor.cur_type().is_const();
+            vi.is_volatile = cursor.cur_type().is_volatile();
+            vi.is_volatile_pointee = cursor.cur_type().kind() == CXTypeKind::Pointer &&
+                                     cursor.cur_type().pointee_type().is_volatile();
             cursor.visit(|c, _: &Cursor| {
                 vi.val = visit_literal(c, unit);
                 CXChildVisitResult::Continue
@@ -637,63 +935,310 @@ fn visit_top(cursor: &Cursor,
                 under_ty = under_ty.canonical_type();
             }
 
-            let ty = conv_ty(ctx, &under_ty, cursor);
+            visit_param_enums(ctx, cursor);
+            let is_template = is_template_instance(&under_ty.canonical_type().declaration());
+            let ty = if is_template {
+                template_instance_ty(&under_ty)
+            } else {
+                conv_ty(ctx, &under_ty, cursor)
+            };
             let typedef = decl_name(ctx, cursor);
             let ti = typedef.typeinfo();
             let mut ti = ti.borrow_mut();
             ti.ty = ty.clone();
             ctx.globals.push(typedef);
 
-            opaque_ty(ctx, &under_ty);
+            if !is_template {
+                opaque_ty(ctx, &under_ty);
+            }
 
             CXChildVisitResult::Continue
         }
+        CXCursorKind::MacroDefinition => {
+            if macro_uses_generic(cursor, unit) {
+                let msg = format!("unsupported feature: `_Generic` in macro `{}`, skipped",
+                                  cursor.spelling());
+                log_err_warn(ctx, &msg, false);
+                return CXChildVisitResult::Continue;
+            }
+            if ctx.options.alias_defines {
+                if let Some(target) = macro_alias_target(cursor, unit) {
+                    ctx.globals.push(GAlias(cursor.spelling(), target));
+                    return CXChildVisitResult::Continue;
+                }
+            }
+            if ctx.options.macro_constants {
+                if let Some(value) = macro_value(cursor, unit) {
+                    ctx.globals.push(GMacro(cursor.spelling(), value));
+                }
+            }
+            CXChildVisitResult::Continue
+        }
         CXCursorKind::FieldDecl => CXChildVisitResult::Continue,
         _ => CXChildVisitResult::Continue,
     }
 }
 
+/// Strips the delimiters and the leading `*` of the lines of a documentation
+/// comment, like `/** ... */` or `///< ...`.
+fn doc_comment(raw: &str) -> String {
+    let mut lines: Vec<&str> = raw.lines().map(doc_comment_line).collect();
+    while lines.last().map_or(false, |l| l.is_empty()) {
+        lines.pop();
+    }
+    let first = lines.iter().position(|l| !l.is_empty()).unwrap_or(lines.len());
+    lines[first..].join("\n")
+}
+
+fn doc_comment_line(line: &str) -> &str {
+    const OPENERS: [&'static str; 10] = ["/**<", "/*!<", "///<", "//!<", "/**", "/*!", "///",
+                                         "//!", "/*", "//"];
+
+    let line = line.trim();
+    let opener_len = OPENERS.iter().find(|o| line.starts_with(**o)).map_or(0, |o| o.len());
+    let mut line = &line[opener_len..];
+    if line.ends_with("*/") {
+        line = &line[..line.len() - 2];
+    }
+    line = line.trim_left();
+    if line.starts_with('*') {
+        line = &line[1..];
+    }
+    line.trim()
+}
+
+fn visit_enum_decl(ctx: &mut ClangParserCtx, cursor: &Cursor) {
+    fwd_decl(ctx, cursor, |ctx_| {
+        let decl = decl_name(ctx_, cursor);
+        let ei = decl.enuminfo();
+        let visited = ctx_.globals.iter().any(|g| {
+            match *g {
+                GEnum(ref e) => rc_addr(e) == rc_addr(&ei),
+                _ => false,
+            }
+        });
+        if visited {
+            return;
+        }
+        cursor.visit(|c, _: &Cursor| {
+            let mut ei_ = ei.borrow_mut();
+            let is_signed = ei_.kind.is_signed();
+            visit_enum(c, is_signed, &mut ei_.items)
+        });
+        ctx_.globals.push(GEnum(ei));
+    });
+}
+
+/// Visits the enums declared in the parameters of a function type, like
+/// `typedef void (*cb)(enum { OK, ERR } status);`, for them to come before the
+/// declaration using them.
+fn visit_param_enums(ctx: &mut ClangParserCtx, cursor: &Cursor) {
+    cursor.visit(|c, _: &Cursor| {
+        match c.kind() {
+            CXCursorKind::EnumDecl => {
+                visit_enum_decl(ctx, c);
+                CXChildVisitResult::Continue
+            }
+            _ => CXChildVisitResult::Recurse,
+        }
+    });
+}
+
+/// Whether the attribute is `__attribute__((noreturn))` or `_Noreturn`, which
+/// libclang doesn't expose.
+fn is_noreturn_attr(cursor: &Cursor, unit: &TranslationUnit) -> bool {
+    unit.tokens(cursor).map_or(false, |tokens| {
+        tokens.iter().any(|t| {
+            match &t.spelling[..] {
+                "noreturn" | "__noreturn__" | "_Noreturn" => true,
+                _ => false,
+            }
+        })
+    })
+}
+
+/// The tokens of a macro definition, its name included.
+fn macro_tokens(cursor: &Cursor, unit: &TranslationUnit) -> Option<Vec<cx::Token>> {
+    let (_, _, _, end) = cursor.extent_end().location();
+    unit.tokens(cursor).map(|mut tokens| {
+        // Older versions of clang include the token following the macro, past
+        // the end of its extent. The continued lines are kept.
+        let past_end = tokens.last().map_or(false, |t| t.location.location().3 > end);
+        if past_end {
+            tokens.pop();
+        }
+        tokens
+    })
+}
+
+/// Whether the body of a macro has a `_Generic` selection, which can't be
+/// represented.
+fn macro_uses_generic(cursor: &Cursor, unit: &TranslationUnit) -> bool {
+    macro_tokens(cursor, unit).map_or(false, |tokens| {
+        tokens.iter().skip(1).any(|t| t.spelling == "_Generic")
+    })
+}
+
+/// Returns the identifier an object-like macro expands to, if its body is
+/// nothing but an identifier.
+fn macro_alias_target(cursor: &Cursor, unit: &TranslationUnit) -> Option<String> {
+    let tokens = match macro_tokens(cursor, unit) {
+        None => return None,
+        Some(tokens) => tokens,
+    };
+
+    if tokens.len() != 2 || tokens[1].kind != CXTokenKind::Identifier {
+        return None;
+    }
+
+    let name = cursor.spelling();
+    if tokens[0].spelling != name || tokens[1].spelling == name {
+        return None;
+    }
+
+    Some(tokens[1].spelling.clone())
+}
+
+/// Returns the literal an object-like macro expands to, if its body is nothing
+/// but an integer, floating-point, character or string literal.
+fn macro_value(cursor: &Cursor, unit: &TranslationUnit) -> Option<MacroValue> {
+    let tokens = match macro_tokens(cursor, unit) {
+        None => return None,
+        Some(tokens) => tokens,
+    };
+
+    // The function-like macros have their parameters as tokens too.
+    if tokens.len() != 2 || tokens[1].kind != CXTokenKind::Literal {
+        return None;
+    }
+
+    let s = &tokens[1].spelling[..];
+    if s.starts_with('"') {
+        unescape(&s[1..s.len() - 1]).map(MacroValue::Str)
+    } else if s.starts_with('\'') {
+        match unescape(&s[1..s.len() - 1]) {
+            Some(ref bytes) if bytes.len() == 1 => Some(MacroValue::Char(bytes[0])),
+            _ => None,
+        }
+    } else {
+        parse_number(s)
+    }
+}
+
+/// Parses an integer or floating-point C literal, ignoring its suffix.
+fn parse_number(s: &str) -> Option<MacroValue> {
+    let is_hex = s.starts_with("0x") || s.starts_with("0X");
+    if !is_hex && s.contains(|c: char| c == '.' || c == 'e' || c == 'E') {
+        let s = s.trim_right_matches(|c: char| c == 'f' || c == 'F' || c == 'l' || c == 'L');
+        return match s.parse::<f64>() {
+            Ok(f) if f.is_finite() => Some(MacroValue::Float(f)),
+            _ => None,
+        };
+    }
+
+    let s = s.trim_right_matches(|c: char| c == 'u' || c == 'U' || c == 'l' || c == 'L');
+    let parsed = if is_hex {
+        u64::from_str_radix(&s[2..], 16)
+    } else if s.len() > 1 && s.starts_with('0') {
+        u64::from_str_radix(&s[1..], 8)
+    } else {
+        s.parse()
+    };
+    parsed.ok().map(|i| {
+        if i <= ::std::i64::MAX as u64 {
+            MacroValue::Int(i as i64)
+        } else {
+            MacroValue::UInt(i)
+        }
+    })
+}
+
+/// The bytes of the contents of a C string or character literal, `None` for the
+/// escapes that aren't supported.
+fn unescape(s: &str) -> Option<Vec<u8>> {
+    let mut bytes = vec![];
+    let mut input = s.bytes();
+    while let Some(b) = input.next() {
+        if b != b'\\' {
+            bytes.push(b);
+            continue;
+        }
+        let unescaped = match input.next() {
+            Some(b'n') => b'\n',
+            Some(b't') => b'\t',
+            Some(b'r') => b'\r',
+            Some(b'0') => 0,
+            Some(c @ b'\\') | Some(c @ b'\'') | Some(c @ b'"') | Some(c @ b'?') => c,
+            _ => return None,
+        };
+        bytes.push(unescaped);
+    }
+    Some(bytes)
+}
+
 fn log_err_warn(ctx: &mut ClangParserCtx, msg: &str, is_err: bool) {
     if is_err {
-        ctx.err_count += 1;
+        ctx.errors.push(msg.to_owned());
         ctx.logger.error(msg)
     } else {
         ctx.logger.warn(msg)
     }
 }
 
-pub fn parse(options: ClangParserOptions, logger: &Logger) -> Result<Vec<Global>, ()> {
+/// Parses the headers, returning the globals and, when recorded, the file of
+/// each of them.
+pub fn parse(options: ClangParserOptions,
+             logger: &Logger)
+             -> Result<(Vec<Global>, Vec<String>), BindgenError> {
+    let opaque_types = try!(compile_whitelist(logger, &options.opaque_types));
     let mut ctx = ClangParserCtx {
         options: options,
         name: HashMap::new(),
         builtin_defs: vec![],
         globals: vec![],
         logger: logger,
-        err_count: 0,
+        errors: vec![],
+        unknown_types: vec![],
+        error_directives: vec![],
+        global_files: vec![],
+        opaque_types: opaque_types,
+        wchar_signed: None,
     };
 
     let ix = cx::Index::create(false, true);
     if ix.is_null() {
         ctx.logger.error("Clang failed to create index");
-        return Err(());
+        return Err(BindgenError::ClangNotFound);
     }
 
-    let flags = CXTranslationUnit_Flags::empty();
-    let unit = TranslationUnit::parse(&ix, "", &ctx.options.clang_args[..], &[], flags);
+    let flags = if ctx.options.alias_defines || ctx.options.macro_constants {
+        CXTranslationUnit_DetailedPreprocessingRecord
+    } else {
+        CXTranslationUnit_Flags::empty()
+    };
+    let (file, unsaved) = input_files(&ctx.options.headers, &ctx.options.stdin);
+    let unit = TranslationUnit::parse(&ix, &file, &ctx.options.clang_args[..], &unsaved[..], flags);
     if unit.is_null() {
         ctx.logger.error("No input files given");
-        return Err(());
+        return Err(BindgenError::ParseFailed("No input files given".to_owned()));
     }
 
     let diags = unit.diags();
     for d in &diags {
         let msg = d.format(Diagnostic::default_opts());
         let is_err = d.severity() >= CXDiagnosticSeverity::Error;
-        log_err_warn(&mut ctx, &msg[..], is_err);
+        // Errors are always reported, they make the parsing fail, and so are
+        // the `#warning` directives of the headers.
+        if is_err || ctx.options.emit_clang_diagnostics || d.option() == "-W#warnings" {
+            log_err_warn(&mut ctx, &msg[..], is_err);
+        }
+        if is_err && is_error_directive(&unit, d) {
+            ctx.error_directives.push(d.spelling());
+        }
     }
 
-    if ctx.err_count > 0 {
-        return Err(());
+    if !ctx.errors.is_empty() {
+        return Err(parse_error(&ctx));
     }
 
     let cursor = unit.cursor();
@@ -702,19 +1247,259 @@ pub fn parse(options: ClangParserOptions, logger: &Logger) -> Result<Vec<Global>
         cursor.visit(|cur, _: &Cursor| ast_dump(cur, 0));
     }
 
-    cursor.visit(|cur, _: &Cursor| visit_top(cur, &mut ctx, &unit));
+    cursor.visit(|cur, _: &Cursor| visit_top_recording(cur, &mut ctx, &unit));
 
     while !ctx.builtin_defs.is_empty() {
         let c = ctx.builtin_defs.remove(0);
-        visit_top(&c.definition(), &mut ctx, &unit);
+        visit_top_recording(&c.definition(), &mut ctx, &unit);
     }
 
     unit.dispose();
     ix.dispose();
 
-    if ctx.err_count > 0 {
-        return Err(());
+    if !ctx.errors.is_empty() {
+        return Err(parse_error(&ctx));
+    }
+
+    let globals = mem::replace(&mut ctx.globals, vec![]);
+    let keep = try!(whitelisted(&ctx, &globals));
+    let files = mem::replace(&mut ctx.global_files, vec![]);
+    let files = files.into_iter().zip(keep.iter()).filter(|&(_, &k)| k).map(|(f, _)| f).collect();
+    let globals = globals.into_iter().zip(keep.iter()).filter(|&(_, &k)| k).map(|(g, _)| g).collect();
+    Ok((globals, files))
+}
+
+/// The file to parse and the files to substitute to the ones on disk. Several
+/// headers, or the standard input, are included in order from a single file,
+/// so the types they share are only seen once. That file is a C++ header when
+/// one of the headers is, clang picking the language from its extension.
+fn input_files(headers: &[String], stdin: &Option<String>) -> (String, Vec<UnsavedFile>) {
+    const STDIN_HEADER: &'static str = "bindgen-stdin.h";
+
+    match headers.len() {
+        0 => return ("".to_owned(), vec![]),
+        1 if headers[0] != "-" => return (headers[0].clone(), vec![]),
+        _ => {}
+    }
+
+    let mut unsaved = vec![];
+    let mut includes = String::new();
+    for header in headers {
+        if header == "-" {
+            let contents = stdin.as_ref().map_or("", |s| &s[..]);
+            unsaved.push(UnsavedFile::new(STDIN_HEADER, contents));
+            includes.push_str(&format!("#include \"{}\"\n", STDIN_HEADER));
+        } else {
+            includes.push_str(&format!("#include \"{}\"\n", header));
+        }
+    }
+    let all_headers = if headers.iter().any(|h| is_cxx_header(h)) {
+        "bindgen-headers.hpp"
+    } else {
+        "bindgen-headers.h"
+    };
+    unsaved.push(UnsavedFile::new(all_headers, &includes));
+    (all_headers.to_owned(), unsaved)
+}
+
+fn is_cxx_header(header: &str) -> bool {
+    match Path::new(header).extension().and_then(|e| e.to_str()) {
+        Some("hpp") | Some("hxx") | Some("hh") | Some("h++") | Some("H") => true,
+        _ => false,
+    }
+}
+
+/// Whether the diagnostic comes from an `#error` directive, by looking at the
+/// tokens clang read on its line.
+fn is_error_directive(unit: &TranslationUnit, d: &Diagnostic) -> bool {
+    let (file, line, col, _) = d.location().location();
+    if file.name().is_none() {
+        return false;
+    }
+    // The diagnostic points at the `#` or at the directive name.
+    match unit.file_tokens(&file, (line, 1), (line, col + "error".len())) {
+        Some(tokens) => {
+            let spellings: Vec<&str> = tokens.iter().map(|t| &t.spelling[..]).collect();
+            spellings.starts_with(&["#", "error"])
+        }
+        None => false,
+    }
+}
+
+/// The error for the messages reported while parsing.
+fn parse_error(ctx: &ClangParserCtx) -> BindgenError {
+    if !ctx.error_directives.is_empty() {
+        BindgenError::ErrorDirective(ctx.error_directives.join("\n"))
+    } else if ctx.unknown_types.is_empty() {
+        BindgenError::ParseFailed(ctx.errors.join("\n"))
+    } else {
+        BindgenError::UnknownType(ctx.unknown_types.join("\n"))
+    }
+}
+
+/// Compiles the patterns of an option list, which match whole names.
+pub fn compile_whitelist(logger: &Logger,
+                         patterns: &[String])
+                         -> Result<Option<RegexSet>, BindgenError> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let anchored: Vec<_> = patterns.iter().map(|p| format!("^(?:{})$", p)).collect();
+    match RegexSet::new(&anchored) {
+        Ok(set) => Ok(Some(set)),
+        Err(e) => {
+            let msg = format!("invalid pattern: {}", e);
+            logger.error(&msg);
+            Err(BindgenError::ParseFailed(msg))
+        }
+    }
+}
+
+/// Whether `name` matches the compiled patterns, `false` without patterns.
+pub fn matches(set: &Option<RegexSet>, name: &str) -> bool {
+    set.as_ref().map_or(false, |s| s.is_match(name))
+}
+
+fn rc_addr<T>(rc: &Rc<T>) -> usize {
+    &**rc as *const T as usize
+}
+
+fn global_addr(g: &Global) -> Option<usize> {
+    match *g {
+        GType(ref ti) => Some(rc_addr(ti)),
+        GComp(ref ci) | GCompDecl(ref ci) => Some(rc_addr(ci)),
+        GEnum(ref ei) | GEnumDecl(ref ei) => Some(rc_addr(ei)),
+        _ => None,
+    }
+}
+
+/// Collects the addresses of the types `ty` refers to.
+fn type_refs(ty: &il::Type, refs: &mut HashSet<usize>) {
+    match *ty {
+        TPtr(ref t, _, _) |
+        TArray(ref t, _, _) => type_refs(t, refs),
+        TFuncPtr(ref sig, _) |
+        TFuncProto(ref sig, _) => {
+            type_refs(&sig.ret_ty, refs);
+            for &(_, ref t) in &sig.args {
+                type_refs(t, refs);
+            }
+        }
+        TNamed(ref ti) => {
+            refs.insert(rc_addr(ti));
+        }
+        TComp(ref ci) => {
+            refs.insert(rc_addr(ci));
+        }
+        TEnum(ref ei) => {
+            refs.insert(rc_addr(ei));
+        }
+        _ => {}
+    }
+}
+
+fn comp_refs(ci: &CompInfo, refs: &mut HashSet<usize>) {
+    for m in &ci.members {
+        match *m {
+            CompMember::Field(ref f) |
+            CompMember::EnumField(_, ref f) => type_refs(&f.ty, refs),
+            CompMember::CompField(ref c, ref f) => {
+                comp_refs(&c.borrow(), refs);
+                type_refs(&f.ty, refs);
+            }
+            CompMember::Comp(ref c) => comp_refs(&c.borrow(), refs),
+            CompMember::Enum(_) => {}
+        }
+    }
+}
+
+fn global_refs(g: &Global, refs: &mut HashSet<usize>) {
+    match *g {
+        GType(ref ti) => type_refs(&ti.borrow().ty, refs),
+        GComp(ref ci) => comp_refs(&ci.borrow(), refs),
+        GVar(ref vi) | GFunc(ref vi) => type_refs(&vi.borrow().ty, refs),
+        _ => {}
+    }
+}
+
+fn global_name(g: &Global) -> String {
+    match *g {
+        GType(ref ti) => ti.borrow().name.clone(),
+        GComp(ref ci) | GCompDecl(ref ci) => ci.borrow().name.clone(),
+        GEnum(ref ei) | GEnumDecl(ref ei) => ei.borrow().name.clone(),
+        GVar(ref vi) | GFunc(ref vi) => vi.borrow().name.clone(),
+        GAlias(ref name, _) | GMacro(ref name, _) => name.clone(),
+        GOther => "*".to_owned(),
+    }
+}
+
+/// Marks the globals to keep, the ones matching the whitelists and the types
+/// they depend on.
+fn whitelisted(ctx: &ClangParserCtx, globals: &[Global]) -> Result<Vec<bool>, BindgenError> {
+    let types = try!(compile_whitelist(ctx.logger, &ctx.options.whitelist_types));
+    let functions = try!(compile_whitelist(ctx.logger, &ctx.options.whitelist_functions));
+    let vars = try!(compile_whitelist(ctx.logger, &ctx.options.whitelist_vars));
+    if types.is_none() && functions.is_none() && vars.is_none() {
+        return Ok(vec![true; globals.len()]);
+    }
+
+    let mut keep: Vec<bool> = globals.iter()
+                                     .map(|g| {
+                                         match *g {
+                                             GType(ref ti) => matches(&types, &ti.borrow().name),
+                                             GComp(ref ci) |
+                                             GCompDecl(ref ci) => matches(&types, &ci.borrow().name),
+                                             GEnum(ref ei) |
+                                             GEnumDecl(ref ei) => matches(&types, &ei.borrow().name),
+                                             GFunc(ref vi) => matches(&functions, &vi.borrow().name),
+                                             GVar(ref vi) => matches(&vars, &vi.borrow().name),
+                                             GAlias(ref name, _) => {
+                                                 matches(&types, name) || matches(&vars, name)
+                                             }
+                                             GMacro(ref name, _) => matches(&vars, name),
+                                             GOther => false,
+                                         }
+                                     })
+                                     .collect();
+
+    // Pull in the types the kept globals refer to, until there are no more,
+    // remembering the first global referring to each of them.
+    let mut refs = HashMap::new();
+    let mut pulled_by = vec![None; globals.len()];
+    loop {
+        for (i, g) in globals.iter().enumerate().filter(|&(i, _)| keep[i]) {
+            let mut g_refs = HashSet::new();
+            global_refs(g, &mut g_refs);
+            for addr in g_refs {
+                refs.entry(addr).or_insert(i);
+            }
+        }
+        let mut changed = false;
+        for (j, g) in globals.iter().enumerate() {
+            if keep[j] {
+                continue;
+            }
+            if let Some(&i) = global_addr(g).and_then(|a| refs.get(&a)) {
+                keep[j] = true;
+                pulled_by[j] = Some(i);
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    if ctx.options.allowlist_report {
+        for (j, by) in pulled_by.iter().enumerate() {
+            if let Some(i) = *by {
+                let msg = format!("`{}` pulled in transitively, referred to by `{}`",
+                                  global_name(&globals[j]),
+                                  global_name(&globals[i]));
+                ctx.logger.warn(&msg);
+            }
+        }
     }
 
-    Ok(ctx.globals)
+    Ok(keep)
 }
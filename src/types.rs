@@ -24,6 +24,10 @@ pub enum Global {
     GVar(Rc<RefCell<VarInfo>>),
     /// A function prototype, like `int func();`.
     GFunc(Rc<RefCell<VarInfo>>),
+    /// An object-like macro aliasing another identifier, like `#define foo bar`.
+    GAlias(String, String),
+    /// An object-like macro expanding to a literal, like `#define MAX 260`.
+    GMacro(String, MacroValue),
     /// Something else.
     GOther,
 }
@@ -71,11 +75,25 @@ impl fmt::Debug for Global {
             GEnumDecl(ref ei) => ei.borrow().fmt(f),
             GVar(ref vi) |
             GFunc(ref vi) => vi.borrow().fmt(f),
+            GAlias(ref name, _) |
+            GMacro(ref name, _) => name.fmt(f),
             GOther => "*".fmt(f),
         }
     }
 }
 
+/// The literal an object-like macro expands to.
+#[derive(Clone, PartialEq, Debug)]
+pub enum MacroValue {
+    Int(i64),
+    /// An integer past `i64::MAX`.
+    UInt(u64),
+    Float(f64),
+    Char(u8),
+    /// The bytes of a string literal, without the terminating nul.
+    Str(Vec<u8>),
+}
+
 /// A function signature.
 #[derive(Clone, PartialEq, Debug)]
 pub struct FuncSig {
@@ -170,6 +188,68 @@ impl Type {
             _ => true,
         }
     }
+
+    /// Whether the type can derive `Copy`, given the names of the types that
+    /// must not be `Copy`
+    pub fn can_derive_copy(&self, no_copy: &[String]) -> bool {
+        match *self {
+            TArray(ref t, _, _) => t.can_derive_copy(no_copy),
+            TNamed(ref ti) => {
+                let ti = ti.borrow();
+                !no_copy.contains(&ti.name) && ti.ty.can_derive_copy(no_copy)
+            }
+            TComp(ref comp) => {
+                let c = comp.borrow();
+                // Unions are emitted as a blob of bytes, which is always `Copy`
+                !no_copy.contains(&c.name) &&
+                (c.kind == CompKind::Union ||
+                 c.members.iter().all(|m| m.can_derive_copy(no_copy)))
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the type can derive `PartialEq`, and with `floats` unset, `Eq`
+    /// and `Hash`
+    pub fn can_derive_eq(&self, floats: bool) -> bool {
+        match *self {
+            TFloat(..) => floats,
+            // Function pointers and the Rust enums don't implement them
+            TFuncPtr(..) | TFuncProto(..) | TEnum(..) => false,
+            TArray(ref t, size, _) => size <= 32 && t.can_derive_eq(floats),
+            TNamed(ref ti) => ti.borrow().ty.can_derive_eq(floats),
+            TComp(ref comp) => {
+                let c = comp.borrow();
+                // Unions and opaque blobs don't derive the comparison traits
+                !c.opaque && c.kind == CompKind::Struct &&
+                c.members.iter().all(|m| m.can_derive_eq(floats))
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether the type can derive `Default`
+    pub fn can_derive_default(&self) -> bool {
+        match *self {
+            // Raw pointers and the Rust enums don't implement `Default`, the
+            // composites always get an implementation
+            TPtr(..) | TEnum(..) => false,
+            TArray(ref t, size, _) => size <= 32 && t.can_derive_default(),
+            TNamed(ref ti) => ti.borrow().ty.can_derive_default(),
+            _ => true,
+        }
+    }
+
+    /// Whether the type contains a pointer or a function pointer
+    pub fn has_pointers(&self) -> bool {
+        match *self {
+            TPtr(..) | TFuncPtr(..) | TFuncProto(..) => true,
+            TArray(ref t, _, _) => t.has_pointers(),
+            TNamed(ref ti) => ti.borrow().ty.has_pointers(),
+            TComp(ref comp) => comp.borrow().members.iter().any(|m| m.has_pointers()),
+            _ => false,
+        }
+    }
 }
 
 /// Describes the layout of an element
@@ -218,6 +298,11 @@ pub enum IKind {
     IULong,
     ILongLong,
     IULongLong,
+    /// `wchar_t`, whose width and signedness depend on the platform, signed
+    /// when the flag is set.
+    IWChar(bool),
+    IChar16,
+    IChar32,
 }
 
 impl IKind {
@@ -234,6 +319,9 @@ impl IKind {
             IULong => false,
             ILongLong => true,
             IULongLong => false,
+            IWChar(signed) => signed,
+            IChar16 => false,
+            IChar32 => false,
         }
     }
 }
@@ -242,6 +330,8 @@ impl IKind {
 pub enum FKind {
     FFloat,
     FDouble,
+    /// `_Float16`, which has no stable Rust equivalent.
+    FFloat16,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -273,6 +363,38 @@ impl CompMember {
             &CompMember::EnumField(_, ref f) => f.ty.layout(),
         }
     }
+
+    pub fn can_derive_copy(&self, no_copy: &[String]) -> bool {
+        match self {
+            &CompMember::Field(ref f) |
+            &CompMember::CompField(_, ref f) |
+            &CompMember::EnumField(_, ref f) => f.ty.can_derive_copy(no_copy),
+            // Anonymous composites are emitted as a blob of bytes
+            &CompMember::Comp(_) |
+            &CompMember::Enum(_) => true,
+        }
+    }
+
+    pub fn can_derive_eq(&self, floats: bool) -> bool {
+        match self {
+            &CompMember::Field(ref f) |
+            &CompMember::CompField(_, ref f) |
+            &CompMember::EnumField(_, ref f) => f.ty.can_derive_eq(floats),
+            // Anonymous composites are emitted as a blob of bytes
+            &CompMember::Comp(_) |
+            &CompMember::Enum(_) => true,
+        }
+    }
+
+    pub fn has_pointers(&self) -> bool {
+        match self {
+            &CompMember::Field(ref f) |
+            &CompMember::CompField(_, ref f) |
+            &CompMember::EnumField(_, ref f) => f.ty.has_pointers(),
+            &CompMember::Comp(ref rc_c) => rc_c.borrow().members.iter().any(|m| m.has_pointers()),
+            &CompMember::Enum(_) => false,
+        }
+    }
 }
 
 /// Is the composed element a struct or an union?
@@ -289,6 +411,11 @@ pub struct CompInfo {
     pub name: String,
     pub members: Vec<CompMember>,
     pub layout: Layout,
+    /// The documentation comment, empty without one.
+    pub comment: String,
+    /// Whether the layout can't be reproduced with the members, like with the
+    /// virtual or multiple inheritance.
+    pub opaque: bool,
 }
 
 impl CompInfo {
@@ -298,6 +425,8 @@ impl CompInfo {
             name: name,
             members: members,
             layout: layout,
+            comment: String::new(),
+            opaque: false,
         }
     }
 }
@@ -314,6 +443,14 @@ pub struct FieldInfo {
     pub name: String,
     pub ty: Type,
     pub bitfields: Option<Vec<(String, u32)>>,
+    /// The offset in bytes of the field, as reported by clang.
+    pub offset: Option<usize>,
+    /// Is the field volatile?
+    pub is_volatile: bool,
+    /// The documentation comment, empty without one.
+    pub comment: String,
+    /// Is the field the C++ base class?
+    pub is_base: bool,
 }
 
 impl FieldInfo {
@@ -322,6 +459,10 @@ impl FieldInfo {
             name: name,
             ty: ty,
             bitfields: bitfields,
+            offset: None,
+            is_volatile: false,
+            comment: String::new(),
+            is_base: false,
         }
     }
 }
@@ -334,6 +475,8 @@ pub struct EnumInfo {
     /// The underlining representation of the enum.
     pub kind: IKind,
     pub layout: Layout,
+    /// The documentation comment, empty without one.
+    pub comment: String,
 }
 
 impl EnumInfo {
@@ -343,6 +486,7 @@ impl EnumInfo {
             items: items,
             kind: kind,
             layout: layout,
+            comment: String::new(),
         }
     }
 }
@@ -358,6 +502,8 @@ impl fmt::Debug for EnumInfo {
 pub struct EnumItem {
     pub name: String,
     pub val: i64,
+    /// The documentation comment, empty without one.
+    pub comment: String,
 }
 
 impl EnumItem {
@@ -365,6 +511,7 @@ impl EnumItem {
         EnumItem {
             name: name,
             val: val,
+            comment: String::new(),
         }
     }
 }
@@ -374,6 +521,8 @@ pub struct TypeInfo {
     pub name: String,
     pub ty: Type,
     pub layout: Layout,
+    /// The documentation comment, empty without one.
+    pub comment: String,
 }
 
 impl TypeInfo {
@@ -382,6 +531,7 @@ impl TypeInfo {
             name: name,
             ty: ty,
             layout: layout,
+            comment: String::new(),
         }
     }
 }
@@ -401,6 +551,20 @@ pub struct VarInfo {
     pub val: Option<i64>,
     /// Is the variable constant?
     pub is_const: bool,
+    /// Is the variable volatile?
+    pub is_volatile: bool,
+    /// Is the variable a pointer to volatile memory?
+    pub is_volatile_pointee: bool,
+    /// Is the function marked as never returning?
+    pub is_noreturn: bool,
+    /// Is the function marked `pure`, without side effects?
+    pub is_pure: bool,
+    /// Is the function marked `const`, depending only on its arguments?
+    pub is_const_fn: bool,
+    /// The documentation comment, empty without one.
+    pub comment: String,
+    /// The mangled symbol of a C++ method, which is named after its class.
+    pub mangled: Option<String>,
 }
 
 impl VarInfo {
@@ -410,6 +574,13 @@ impl VarInfo {
             ty: ty,
             val: None,
             is_const: false,
+            is_volatile: false,
+            is_volatile_pointee: false,
+            is_noreturn: false,
+            is_pure: false,
+            is_const_fn: false,
+            comment: String::new(),
+            mangled: None,
         }
     }
 }
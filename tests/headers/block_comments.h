@@ -0,0 +1,9 @@
+typedef int my_int;
+
+struct point {
+    my_int x;
+};
+
+extern my_int counter;
+
+my_int next(void);
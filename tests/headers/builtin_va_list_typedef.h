@@ -0,0 +1,3 @@
+typedef __builtin_va_list my_va_list;
+
+void takes_va_list(my_va_list args);
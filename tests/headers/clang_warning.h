@@ -0,0 +1 @@
+int foo = 1 / 0;
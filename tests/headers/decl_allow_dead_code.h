@@ -0,0 +1,5 @@
+struct point {
+    int x;
+};
+
+int area(struct point p);
@@ -0,0 +1,12 @@
+struct handle {
+    int fd;
+};
+
+struct handle_state {
+    int x;
+};
+
+void handle(void);
+void handle_reset(void);
+int keep(void);
+int uses_handle(struct handle *h);
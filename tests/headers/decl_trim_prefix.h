@@ -0,0 +1,7 @@
+struct mylib_ctx {
+    int x;
+};
+
+int mylib_init(struct mylib_ctx *ctx);
+int mylib_run(void);
+int run(void);
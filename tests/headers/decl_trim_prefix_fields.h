@@ -0,0 +1,10 @@
+struct mylib_ctx {
+    int x;
+};
+
+struct mylib_owner {
+    struct mylib_ctx *mylib_ctx;
+    int mylib_init;
+};
+
+int mylib_init(struct mylib_ctx *mylib_ctx);
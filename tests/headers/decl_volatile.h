@@ -0,0 +1,2 @@
+extern volatile unsigned int counter;
+extern volatile unsigned int *const REG;
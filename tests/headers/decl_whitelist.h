@@ -0,0 +1,12 @@
+struct SDL_Rect {
+    int x;
+    int y;
+};
+
+struct other {
+    int z;
+};
+
+struct SDL_Rect SDL_GetRect(void);
+void other_fn(void);
+extern int SDL_count;
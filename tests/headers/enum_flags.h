@@ -0,0 +1,5 @@
+enum Flags {
+    FLAG_A = 1,
+    FLAG_B = 2,
+    FLAG_C = 4
+};
@@ -0,0 +1,6 @@
+enum Perms {
+    PERM_READ = 1,
+    PERM_WRITE = 2,
+    PERM_EXEC = 4,
+    PERM_ALL = PERM_READ | PERM_WRITE | PERM_EXEC
+};
@@ -0,0 +1,5 @@
+enum access {
+    ACCESS_READ = 1,
+    ACCESS_WRITE = 2,
+    ACCESS_ALL = -1,
+};
@@ -0,0 +1,10 @@
+enum Mask {
+    MASK_NONE = 0,
+    MASK_ALL = 0xFFFFFFFF,
+};
+
+enum Wide {
+    WIDE_LOW = 1,
+    WIDE_HIGH = 1ull << 40,
+    WIDE_ALL = 0xFFFFFFFFFFFFFFFFull,
+};
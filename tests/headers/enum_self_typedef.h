@@ -0,0 +1,6 @@
+typedef enum Color {
+    RED,
+    GREEN
+} Color;
+
+Color current_color(void);
@@ -0,0 +1,5 @@
+#ifndef SUPPORTED_PLATFORM
+#error "this platform is not supported"
+#endif
+
+int foo;
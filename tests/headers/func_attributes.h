@@ -0,0 +1,3 @@
+void die(const char *msg) __attribute__((noreturn));
+int square(int x) __attribute__((const));
+int length(const char *s) __attribute__((pure));
@@ -0,0 +1,2 @@
+int add(int a, int b);
+void reset(void);
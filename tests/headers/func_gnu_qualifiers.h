@@ -0,0 +1,3 @@
+void copy(char *__restrict__ dst, __const__ char *__restrict src);
+void peek(__const int *p);
+void poke(__volatile__ int *p);
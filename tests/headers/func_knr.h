@@ -0,0 +1,7 @@
+int add(a, b, c)
+    int a;
+    char b;
+    float c;
+{
+    return a + b + c;
+}
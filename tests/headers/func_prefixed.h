@@ -0,0 +1,2 @@
+int mylib_open(const char *path);
+extern int mylib_errno;
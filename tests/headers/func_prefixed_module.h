@@ -0,0 +1,7 @@
+struct gl_color {
+    float r, g, b;
+};
+
+void gl_clear(struct gl_color color);
+void gl_flush(void);
+int version(void);
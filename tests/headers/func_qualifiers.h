@@ -0,0 +1,3 @@
+void copy(char *restrict dst, const char *restrict src);
+void peek(const int *p);
+void poke(volatile int *p);
@@ -0,0 +1,4 @@
+struct b_type { int x; };
+int zeta(void);
+int alpha(void);
+struct a_type { int y; };
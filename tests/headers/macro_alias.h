@@ -0,0 +1,9 @@
+typedef int real_type;
+int real_func(int x);
+
+#define MYFUNC real_func
+#define MYTYPE real_type
+#define MISSING nothing_here
+#define NOT_AN_ALIAS real_func + 1
+#define CONTINUED_ALIAS real_func \
+    + 1
@@ -0,0 +1,10 @@
+#define MAX_PATH 260
+#define VERSION_STRING "1.2.3"
+#define RATIO 0.5
+#define SEPARATOR ','
+#define HEX_MASK 0xFFu
+#define BIG 0x100000000
+#define SQUARE(x) ((x) * (x))
+#define NOT_A_LITERAL MAX_PATH + 1
+#define CONTINUED 1 \
+    + 2
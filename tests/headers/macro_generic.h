@@ -0,0 +1,5 @@
+#define VERSION 3
+#define cbrt_any(x) _Generic((x), float: cbrtf, default: cbrt)(x)
+#define SCALE 2.5
+#define fabs_any(x) \
+    _Generic((x), float: fabsf, default: fabs)(x)
@@ -0,0 +1,2 @@
+#define ALL_BITS 0xFFFFFFFF
+#define ALL_BITS_64 0xFFFFFFFFFFFFFFFFull
@@ -0,0 +1,3 @@
+#include "does_not_exist.h"
+
+int foo;
@@ -0,0 +1,3 @@
+struct flag {
+    bool set;
+};
@@ -0,0 +1,9 @@
+#ifndef MULTI_HEADER_POINT_H
+#define MULTI_HEADER_POINT_H
+
+struct point {
+    int x;
+    int y;
+};
+
+#endif
@@ -0,0 +1,6 @@
+#include "multi_header_point.h"
+
+struct segment {
+    struct point from;
+    struct point to;
+};
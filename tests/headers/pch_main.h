@@ -0,0 +1,3 @@
+#include "pch_types.h"
+
+struct pch_point pch_origin(void);
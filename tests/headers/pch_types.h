@@ -0,0 +1,9 @@
+#ifndef PCH_TYPES_H
+#define PCH_TYPES_H
+
+struct pch_point {
+    int x;
+    int y;
+};
+
+#endif
@@ -0,0 +1,3 @@
+#include "source_files_included.h"
+
+int in_main;
@@ -0,0 +1,8 @@
+struct pod {
+    int a;
+    int b;
+};
+
+struct not_pod {
+    int *p;
+};
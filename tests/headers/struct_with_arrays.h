@@ -0,0 +1,5 @@
+struct buffers {
+    int data[16];
+    int m[4][4];
+    unsigned char large[64];
+};
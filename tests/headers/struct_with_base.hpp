@@ -0,0 +1,7 @@
+struct Base {
+    int x;
+};
+
+struct Derived : Base {
+    int y;
+};
@@ -0,0 +1,6 @@
+typedef unsigned int u32;
+
+struct str {
+    u32 type;
+    int bool;
+};
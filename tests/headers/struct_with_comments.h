@@ -0,0 +1,20 @@
+/**
+ * A point on the screen.
+ *
+ * The origin is the top left corner.
+ */
+struct point {
+    int x; ///< The column.
+    /** The row. */
+    int y;
+};
+
+/// The directions.
+enum direction {
+    /// Towards the top.
+    UP,
+    DOWN,
+};
+
+/// Moves the point.
+void move_point(struct point *p, enum direction dir);
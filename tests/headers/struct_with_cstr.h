@@ -0,0 +1,4 @@
+struct user {
+    char name[32];
+    int age;
+};
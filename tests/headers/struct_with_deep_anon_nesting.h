@@ -0,0 +1,13 @@
+struct outer {
+    char tag;
+    struct {
+        short kind;
+        struct {
+            char flag;
+            union {
+                int i;
+                float f;
+            };
+        };
+    };
+};
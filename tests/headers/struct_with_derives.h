@@ -0,0 +1,9 @@
+struct point {
+    int x;
+    int y;
+};
+
+struct shape {
+    struct point origin;
+    int (*area)(int scale);
+};
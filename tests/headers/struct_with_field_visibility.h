@@ -0,0 +1,8 @@
+struct Point {
+    int x;
+    int y;
+};
+
+struct Open {
+    int a;
+};
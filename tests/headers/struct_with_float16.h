@@ -0,0 +1,4 @@
+struct half_pair {
+    _Float16 a;
+    _Float16 b;
+};
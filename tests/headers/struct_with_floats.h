@@ -0,0 +1,5 @@
+struct sample {
+    int id;
+    float weight;
+    double coords[3];
+};
@@ -0,0 +1,4 @@
+struct foo {
+    unsigned x;
+    signed y;
+};
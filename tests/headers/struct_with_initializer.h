@@ -0,0 +1,6 @@
+struct buffer {
+    int len;
+    char data[16];
+};
+
+void buffer_init(struct buffer *b);
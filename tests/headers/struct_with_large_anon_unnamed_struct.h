@@ -0,0 +1,6 @@
+struct foo {
+    int x;
+    struct {
+        int values[40];
+    };
+};
@@ -0,0 +1,4 @@
+struct pair {
+    char tag;
+    int value;
+};
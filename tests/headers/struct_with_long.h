@@ -0,0 +1,6 @@
+struct sizes {
+    long a;
+    unsigned long b;
+    int c;
+    int d;
+};
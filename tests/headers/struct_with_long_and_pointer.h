@@ -0,0 +1,4 @@
+struct node {
+    long value;
+    struct node *next;
+};
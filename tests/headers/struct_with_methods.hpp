@@ -0,0 +1,7 @@
+struct Counter {
+    int count;
+
+    int get() const;
+    void add(int n);
+    static Counter make(int count);
+};
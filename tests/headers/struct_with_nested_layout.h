@@ -0,0 +1,10 @@
+struct inner {
+    char a;
+    int b;
+};
+
+struct outer {
+    char c;
+    struct inner i;
+    double d;
+};
@@ -0,0 +1,16 @@
+struct Copyable {
+    int a;
+    void (*cb)(int);
+};
+
+struct Handle {
+    int fd;
+};
+
+struct WithHandle {
+    struct Handle h;
+};
+
+struct WithArray {
+    int a[33];
+};
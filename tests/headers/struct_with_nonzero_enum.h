@@ -0,0 +1,6 @@
+enum state { STATE_ON = 1, STATE_OFF = 2 };
+
+struct device {
+    int id;
+    enum state state;
+};
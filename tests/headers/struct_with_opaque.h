@@ -0,0 +1,13 @@
+struct internal {
+    double d;
+    union {
+        int i;
+        float f;
+    } u;
+    int bits : 3;
+};
+
+struct handle {
+    struct internal inner;
+    int id;
+};
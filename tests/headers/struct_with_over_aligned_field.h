@@ -0,0 +1,4 @@
+struct over_aligned {
+    char a;
+    int b __attribute__ ((aligned (16)));
+};
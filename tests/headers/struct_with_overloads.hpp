@@ -0,0 +1,6 @@
+struct Shape {
+    int sides;
+
+    void scale(int factor);
+    void scale(double factor);
+};
@@ -0,0 +1,3 @@
+struct str {
+    int type;
+};
@@ -0,0 +1,4 @@
+struct list {
+    int *items;
+    unsigned long count;
+};
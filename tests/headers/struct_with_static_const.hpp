@@ -0,0 +1,4 @@
+struct Limits {
+    static const int max = 10;
+    int value;
+};
@@ -0,0 +1,5 @@
+struct foo {
+    wchar_t a;
+    char16_t b;
+    char32_t c;
+};
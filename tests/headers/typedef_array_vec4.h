@@ -0,0 +1,3 @@
+typedef float vec4[4];
+
+vec4 *vec4_normalize(vec4 *v);
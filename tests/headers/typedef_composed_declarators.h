@@ -0,0 +1,3 @@
+typedef char *(*str_fn)(void);
+typedef const char *(*name_fn)(const void *);
+typedef int *ptr_array[3];
@@ -0,0 +1 @@
+typedef decltype(1.0) my_double;
@@ -0,0 +1 @@
+typedef void (*cb)(enum { OK, ERR } status);
@@ -0,0 +1,2 @@
+typedef struct a_ *A;
+typedef struct b_ *B;
@@ -0,0 +1,14 @@
+template <typename T>
+struct Vec {
+    T *data;
+    unsigned len;
+    unsigned cap;
+};
+
+template struct Vec<int>;
+
+typedef Vec<int> IntVec;
+
+extern "C" {
+    void int_vec_push(IntVec *v, int value);
+}
@@ -0,0 +1,12 @@
+template <typename T>
+struct vector {
+    T *data;
+    unsigned len;
+    unsigned cap;
+};
+
+typedef vector<int> IntVec;
+
+extern "C" {
+    unsigned int_vec_len(const IntVec *v);
+}
@@ -0,0 +1,5 @@
+typedef typeof(1 + 1) my_int;
+
+struct holder {
+    typeof(2L) value;
+};
@@ -0,0 +1 @@
+typedef int (*printf_t)(const char *fmt, ...);
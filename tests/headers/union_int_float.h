@@ -0,0 +1,4 @@
+union number {
+    int i;
+    float f;
+};
@@ -0,0 +1,3 @@
+#warning "this header is deprecated"
+
+int foo;
@@ -1,8 +1,9 @@
+use std::cell::RefCell;
 use std::io::{stderr, Write};
 use std::process::{Command, Stdio};
 
 use bindgen;
-use bindgen::{Logger, BindgenOptions};
+use bindgen::{BindgenError, Logger, BindgenOptions};
 
 use syntax::ast;
 use syntax::codemap;
@@ -25,9 +26,35 @@ impl Logger for TestLogger {
     }
 }
 
+/// Keeps the messages it receives, for the tests checking diagnostics.
+#[derive(Debug)]
+pub struct CollectingLogger {
+    pub errors: RefCell<Vec<String>>,
+    pub warnings: RefCell<Vec<String>>,
+}
+
+impl CollectingLogger {
+    pub fn new() -> CollectingLogger {
+        CollectingLogger {
+            errors: RefCell::new(vec![]),
+            warnings: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl Logger for CollectingLogger {
+    fn error(&self, msg: &str) {
+        self.errors.borrow_mut().push(msg.to_owned());
+    }
+
+    fn warn(&self, msg: &str) {
+        self.warnings.borrow_mut().push(msg.to_owned());
+    }
+}
+
 pub fn generate_bindings(mut options: BindgenOptions,
                          filename: &str)
-                         -> Result<Vec<P<ast::Item>>, ()> {
+                         -> Result<Vec<P<ast::Item>>, BindgenError> {
     if filename.ends_with("hpp") {
         options.clang_args.push("-std=c++11".to_string());
         options.clang_args.push("-Wno-narrowing".to_string());
@@ -5,3 +5,20 @@ fn test_builtin_va_list() {
 	let bindings = bindgen::builder().header("tests/headers/builtin_va_list.h").builtins().generate().unwrap().to_string();
     assert!(bindings.contains("__builtin_va_list"));
 }
+
+#[test]
+fn test_builtin_va_list_typedef() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/builtin_va_list_typedef.h")
+        .builtins()
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert_eq!(bindings.matches("pub type my_va_list = __builtin_va_list;").count(), 1);
+    assert_eq!(bindings.matches("pub type __builtin_va_list =").count(), 1);
+    if cfg!(target_arch = "x86_64") {
+        assert!(bindings.contains("pub type __builtin_va_list = [__va_list_tag; 1usize];"));
+        assert_eq!(bindings.matches("pub struct __va_list_tag").count(), 1);
+    }
+}
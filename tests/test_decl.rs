@@ -1,4 +1,6 @@
-use support::assert_bind_eq;
+use bindgen;
+use bindgen::BindgenOptions;
+use support::{CollectingLogger, assert_bind_eq};
 
 #[test]
 fn ptr_to_array() {
@@ -8,3 +10,222 @@ fn ptr_to_array() {
         }
     ");
 }
+
+#[test]
+fn allow_dead_code_per_item() {
+    let mut options: BindgenOptions = Default::default();
+    options.allow_dead_code = false;
+    options.allow_dead_code_per_item = true;
+    assert_bind_eq(options, "headers/decl_allow_dead_code.h", "
+        #[allow(dead_code)]
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct point {
+            pub x: ::std::os::raw::c_int,
+        }
+        #[allow(dead_code)]
+        impl ::std::default::Default for point {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[allow(dead_code)]
+        extern \"C\" {
+            pub fn area(p: point) -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn allow_dead_code_module() {
+    let header = "tests/headers/decl_allow_dead_code.h";
+    let bindings = bindgen::builder().header(header).generate().unwrap().to_string();
+    assert!(bindings.contains("#![allow(dead_code,"));
+
+    let bindings = bindgen::builder()
+        .header(header)
+        .allow_dead_code(false)
+        .generate()
+        .unwrap()
+        .to_string();
+    assert!(!bindings.contains("dead_code"));
+}
+
+#[test]
+fn trim_prefix() {
+    let mut options: BindgenOptions = Default::default();
+    options.trim_prefix = "mylib_".to_owned();
+    assert_bind_eq(options, "headers/decl_trim_prefix.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct ctx {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for ctx {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            #[link_name = \"mylib_init\"]
+            pub fn init(ctx: *mut ctx) -> ::std::os::raw::c_int;
+            pub fn mylib_run() -> ::std::os::raw::c_int;
+            pub fn run() -> ::std::os::raw::c_int;
+        }
+    ");
+
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/decl_trim_prefix.h")
+        .trim_prefix("mylib_")
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("`run` would be ambiguous, not renaming `mylib_run`")));
+}
+
+#[test]
+fn trim_prefix_keeps_field_names() {
+    let mut options: BindgenOptions = Default::default();
+    options.trim_prefix = "mylib_".to_owned();
+    assert_bind_eq(options, "headers/decl_trim_prefix_fields.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct ctx {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for ctx {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct owner {
+            pub mylib_ctx: *mut ctx,
+            pub mylib_init: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for owner {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            #[link_name = \"mylib_init\"]
+            pub fn init(mylib_ctx: *mut ctx) -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn trim_prefix_reexport() {
+    let mut options: BindgenOptions = Default::default();
+    options.trim_prefix = "mylib_".to_owned();
+    options.trim_prefix_reexport = true;
+    assert_bind_eq(options, "headers/decl_trim_prefix.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct mylib_ctx {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for mylib_ctx {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub fn mylib_init(ctx: *mut mylib_ctx) -> ::std::os::raw::c_int;
+            pub fn mylib_run() -> ::std::os::raw::c_int;
+            pub fn run() -> ::std::os::raw::c_int;
+        }
+        pub use self::mylib_ctx as ctx;
+        pub use self::mylib_init as init;
+    ");
+}
+
+#[test]
+fn blocklist_item() {
+    let logger = CollectingLogger::new();
+    let bindings = bindgen::builder()
+        .header("tests/headers/decl_blocklist.h")
+        .blocklist_item("handle.*")
+        .log(&logger)
+        .generate()
+        .unwrap()
+        .to_string();
+    assert!(!bindings.contains("pub struct handle_state"));
+    assert!(!bindings.contains("pub fn handle"));
+    assert!(bindings.contains("pub fn keep"));
+    assert!(bindings.contains("pub fn uses_handle"));
+    // `uses_handle` still refers to `handle`, which is kept opaque.
+    assert!(bindings.contains("pub struct handle {\n    pub _bindgen_data_: [u32; 1usize],"));
+    assert!(!bindings.contains("pub fd"));
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("`uses_handle` refers to the blocklisted `handle`")));
+}
+
+#[test]
+fn volatile_accessors() {
+    let mut options: BindgenOptions = Default::default();
+    options.volatile_accessors = true;
+    assert_bind_eq(options, "headers/decl_volatile.h", "
+        pub unsafe fn read_counter() -> ::std::os::raw::c_uint {
+            ::std::ptr::read_volatile(&counter)
+        }
+        pub unsafe fn write_counter(value: ::std::os::raw::c_uint) {
+            ::std::ptr::write_volatile(&mut counter, value)
+        }
+        pub unsafe fn read_REG() -> ::std::os::raw::c_uint {
+            ::std::ptr::read_volatile(REG)
+        }
+        pub unsafe fn write_REG(value: ::std::os::raw::c_uint) {
+            ::std::ptr::write_volatile(REG, value)
+        }
+        extern \"C\" {
+            pub static mut counter: ::std::os::raw::c_uint;
+            pub static REG: *mut ::std::os::raw::c_uint;
+        }
+    ");
+}
+
+#[test]
+fn whitelist_function() {
+    let mut options: BindgenOptions = Default::default();
+    options.whitelist_functions.push("SDL_.*".to_owned());
+    assert_bind_eq(options, "headers/decl_whitelist.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct SDL_Rect {
+            pub x: ::std::os::raw::c_int,
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for SDL_Rect {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub fn SDL_GetRect() -> SDL_Rect;
+        }
+    ");
+}
+
+#[test]
+fn whitelist_type_and_var() {
+    let mut options: BindgenOptions = Default::default();
+    options.whitelist_types.push("other".to_owned());
+    options.whitelist_vars.push("SDL_count".to_owned());
+    assert_bind_eq(options, "headers/decl_whitelist.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct other {
+            pub z: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for other {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub static mut SDL_count: ::std::os::raw::c_int;
+        }
+    ");
+}
@@ -0,0 +1,124 @@
+use bindgen;
+use support::CollectingLogger;
+
+#[test]
+fn missing_include() {
+    let logger = CollectingLogger::new();
+    let result = bindgen::builder()
+        .header("tests/headers/missing_include.h")
+        .log(&logger)
+        .generate();
+
+    assert!(result.is_err());
+    assert!(logger.errors.borrow().iter().any(|e| e.contains("does_not_exist.h")));
+}
+
+#[test]
+fn clang_warnings() {
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/clang_warning.h")
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings.borrow().iter().any(|w| w.contains("division by zero")));
+
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/clang_warning.h")
+        .emit_clang_diagnostics(false)
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings.borrow().is_empty());
+}
+
+#[test]
+fn parse_error_message() {
+    let result = bindgen::builder().header("tests/headers/missing_include.h").generate();
+
+    match result {
+        Err(bindgen::BindgenError::ParseFailed(msg)) => assert!(msg.contains("does_not_exist.h")),
+        _ => panic!("expected a parse error"),
+    }
+}
+
+#[test]
+fn error_directive() {
+    let result = bindgen::builder().header("tests/headers/error_directive.h").generate();
+
+    match result {
+        Err(bindgen::BindgenError::ErrorDirective(msg)) => {
+            assert!(msg.contains("this platform is not supported"))
+        }
+        _ => panic!("expected an #error"),
+    }
+
+    assert!(bindgen::builder()
+        .header("tests/headers/error_directive.h")
+        .clang_arg("-DSUPPORTED_PLATFORM")
+        .generate()
+        .is_ok());
+}
+
+#[test]
+fn warning_directive() {
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/warning_directive.h")
+        .emit_clang_diagnostics(false)
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings.borrow().iter().any(|w| w.contains("this header is deprecated")));
+}
+
+#[test]
+fn generic_macro_skipped() {
+    let logger = CollectingLogger::new();
+    let bindings = bindgen::builder()
+        .header("tests/headers/macro_generic.h")
+        .generate_macro_constants(true)
+        .log(&logger)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("unsupported feature: `_Generic`") && w.contains("cbrt_any")));
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("unsupported feature: `_Generic`") && w.contains("fabs_any")));
+    assert!(bindings.contains("pub const VERSION"));
+    assert!(bindings.contains("pub const SCALE"));
+    assert!(!bindings.contains("cbrt_any"));
+    assert!(!bindings.contains("fabs_any"));
+}
+
+#[test]
+fn recursive_allowlist_report() {
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/decl_whitelist.h")
+        .whitelist_function("SDL_.*")
+        .emit_recursive_allowlist_report(true)
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("`SDL_Rect` pulled in") && w.contains("`SDL_GetRect`")));
+
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/decl_whitelist.h")
+        .whitelist_function("SDL_.*")
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings.borrow().is_empty());
+}
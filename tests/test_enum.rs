@@ -1,3 +1,4 @@
+use bindgen;
 use bindgen::BindgenOptions;
 use support::assert_bind_eq;
 
@@ -139,3 +140,233 @@ fn with_overflowed_enum_value() {
         const Big: Bar = 2;
     ");
 }
+
+#[test]
+fn with_self_named_typedef() {
+    assert_bind_eq(Default::default(), "headers/enum_self_typedef.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum Color { RED = 0, GREEN = 1, }
+        extern \"C\" {
+            pub fn current_color() -> Color;
+        }
+    ");
+}
+
+#[test]
+fn with_constified_enum_newtype() {
+    let mut options = default_without_rust_enums();
+    options.constified_enum_newtype.push("Foo".to_owned());
+    assert_bind_eq(options, "headers/enum.h", "
+        #[repr(transparent)]
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        #[derive(Debug)]
+        pub struct Foo(pub u32);
+        impl Foo {
+            pub const Bar: Foo = Foo(0);
+            pub const Qux: Foo = Foo(1);
+        }
+        type Neg = i32;
+        const MinusOne: Neg = -1;
+        const One: Neg = 1;
+    ");
+}
+
+#[test]
+fn with_enum_with_unknown() {
+    let mut options: BindgenOptions = Default::default();
+    options.enum_with_unknown.push("Neg".to_owned());
+    assert_bind_eq(options, "headers/enum.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum Foo { Bar = 0, Qux = 1, }
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        #[derive(Debug)]
+        pub enum Neg { MinusOne, One, __Unknown(i32), }
+        impl ::std::convert::From<i32> for Neg {
+            fn from(value: i32) -> Self {
+                match value {
+                    -1 => Neg::MinusOne,
+                    1 => Neg::One,
+                    value => Neg::__Unknown(value),
+                }
+            }
+        }
+        impl ::std::convert::From<Neg> for i32 {
+            fn from(value: Neg) -> Self {
+                match value {
+                    Neg::MinusOne => -1,
+                    Neg::One => 1,
+                    Neg::__Unknown(value) => value,
+                }
+            }
+        }
+    ");
+}
+
+#[test]
+fn with_discriminant_type_suffix() {
+    let mut options: BindgenOptions = Default::default();
+    options.enum_discriminant_type_suffix = true;
+    assert_bind_eq(options, "headers/enum.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum Foo { Bar = 0u32, Qux = 1u32, }
+        #[derive(Copy, Clone)]
+        #[repr(i32)]
+        #[derive(Debug)]
+        pub enum Neg { MinusOne = -1i32, One = 1i32, }
+    ");
+    let mut options = default_without_rust_enums();
+    options.enum_discriminant_type_suffix = true;
+    assert_bind_eq(options, "headers/enum.h", "
+        type Foo = u32;
+        const Bar: Foo = 0u32;
+        const Qux: Foo = 1u32;
+        type Neg = i32;
+        const MinusOne: Neg = -1i32;
+        const One: Neg = 1i32;
+    ");
+}
+
+#[test]
+fn with_bitfield_enum() {
+    let mut options: BindgenOptions = Default::default();
+    options.bitfield_enums.push("Flags".to_owned());
+    assert_bind_eq(options, "headers/enum_flags.h", "
+        #[repr(transparent)]
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        #[derive(Debug)]
+        pub struct Flags(pub u32);
+        impl Flags {
+            pub const FLAG_A: Flags = Flags(1);
+            pub const FLAG_B: Flags = Flags(2);
+            pub const FLAG_C: Flags = Flags(4);
+        }
+        impl Flags {
+            pub fn empty() -> Self { Flags(0) }
+            pub fn all() -> Self { Flags(7) }
+            pub fn contains(&self, other: Self) -> bool { (self.0 & other.0) == other.0 }
+        }
+        impl ::std::ops::BitOr for Flags {
+            type Output = Self;
+            fn bitor(self, other: Self) -> Self { Flags(self.0 | other.0) }
+        }
+        impl ::std::ops::BitAnd for Flags {
+            type Output = Self;
+            fn bitand(self, other: Self) -> Self { Flags(self.0 & other.0) }
+        }
+        impl ::std::ops::BitOrAssign for Flags {
+            fn bitor_assign(&mut self, other: Self) { self.0 |= other.0; }
+        }
+        impl ::std::ops::Not for Flags {
+            type Output = Self;
+            fn not(self) -> Self { Flags(!self.0) }
+        }
+    ");
+}
+
+#[test]
+fn with_bitfield_enum_combined_item() {
+    let mut options: BindgenOptions = Default::default();
+    options.bitfield_enums.push("Perms".to_owned());
+    assert_bind_eq(options, "headers/enum_flags_combined.h", "
+        #[repr(transparent)]
+        #[derive(Copy, Clone, PartialEq, Eq)]
+        #[derive(Debug)]
+        pub struct Perms(pub u32);
+        impl Perms {
+            pub const PERM_READ: Perms = Perms(1);
+            pub const PERM_WRITE: Perms = Perms(2);
+            pub const PERM_EXEC: Perms = Perms(4);
+            pub const PERM_ALL: Perms = Perms(7);
+        }
+        impl Perms {
+            pub fn empty() -> Self { Perms(0) }
+            pub fn all() -> Self { Perms(7) }
+            pub fn contains(&self, other: Self) -> bool { (self.0 & other.0) == other.0 }
+        }
+        impl ::std::ops::BitOr for Perms {
+            type Output = Self;
+            fn bitor(self, other: Self) -> Self { Perms(self.0 | other.0) }
+        }
+        impl ::std::ops::BitAnd for Perms {
+            type Output = Self;
+            fn bitand(self, other: Self) -> Self { Perms(self.0 & other.0) }
+        }
+        impl ::std::ops::BitOrAssign for Perms {
+            fn bitor_assign(&mut self, other: Self) { self.0 |= other.0; }
+        }
+        impl ::std::ops::Not for Perms {
+            type Output = Self;
+            fn not(self) -> Self { Perms(!self.0) }
+        }
+    ");
+}
+
+#[test]
+fn with_bitfield_enum_pattern() {
+    let bindings = |pattern: &str| {
+        bindgen::builder()
+            .header("tests/headers/enum_flags_combined.h")
+            .bitfield_enum(pattern)
+            .generate()
+            .unwrap()
+            .to_string()
+    };
+    assert!(bindings("Per.*").contains("pub struct Perms(pub u32);"));
+    assert_eq!(bindings("Per.*"), bindings("Perms"));
+    // The patterns match whole names.
+    assert!(!bindings("Per").contains("pub struct Perms"));
+
+    let invalid = bindgen::builder()
+        .header("tests/headers/enum_flags_combined.h")
+        .bitfield_enum("Per(")
+        .generate();
+    match invalid {
+        Err(bindgen::BindgenError::ParseFailed(msg)) => assert!(msg.contains("invalid pattern")),
+        _ => panic!("expected the pattern to be rejected"),
+    }
+}
+
+#[test]
+fn with_flag_constants_type() {
+    let mut options: BindgenOptions = Default::default();
+    options.flag_constant_types.push(("access".to_owned(), "u32".to_owned()));
+    assert_bind_eq(options, "headers/enum_flags_signed.h", "
+        type access = u32;
+        const ACCESS_READ: access = 1;
+        const ACCESS_WRITE: access = 2;
+        const ACCESS_ALL: access = 4294967295;
+    ");
+}
+
+#[test]
+fn with_large_values() {
+    assert_bind_eq(Default::default(), "headers/enum_large_values.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum Mask { MASK_NONE = 0, MASK_ALL = 4294967295, }
+        #[derive(Copy, Clone)]
+        #[repr(u64)]
+        #[derive(Debug)]
+        pub enum Wide {
+            WIDE_LOW = 1,
+            WIDE_HIGH = 1099511627776,
+            WIDE_ALL = 18446744073709551615,
+        }
+    ");
+    assert_bind_eq(default_without_rust_enums(), "headers/enum_large_values.h", "
+        type Mask = u32;
+        const MASK_NONE: Mask = 0;
+        const MASK_ALL: Mask = 4294967295;
+        type Wide = u64;
+        const WIDE_LOW: Wide = 1;
+        const WIDE_HIGH: Wide = 1099511627776;
+        const WIDE_ALL: Wide = 18446744073709551615;
+    ");
+}
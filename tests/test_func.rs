@@ -1,3 +1,5 @@
+use bindgen;
+use bindgen::BindgenOptions;
 use support::assert_bind_eq;
 
 #[test]
@@ -46,6 +48,18 @@ fn func_no_proto() {
     ");
 }
 
+#[test]
+fn func_knr_definition() {
+    assert_bind_eq(Default::default(), "headers/func_knr.h", "
+        extern \"C\" {
+            pub fn add(a: ::std::os::raw::c_int,
+                       b: ::std::os::raw::c_int,
+                       c: ::std::os::raw::c_double)
+                       -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
 #[test]
 fn with_func_ptr_arg() {
     assert_bind_eq(Default::default(), "headers/func_with_func_ptr_arg.h", "
@@ -65,3 +79,306 @@ fn with_array_arg() {
         }
     ");
 }
+
+#[test]
+fn sorted_by_name() {
+    let options = BindgenOptions { sort_functions_by_name: true, .. Default::default() };
+    assert_bind_eq(options, "headers/func_unsorted.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct b_type {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for b_type {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct a_type {
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for a_type {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub fn alpha() -> ::std::os::raw::c_int;
+            pub fn zeta() -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn dynamic_library_runtime() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/func_dynamic.h")
+        .dynamic_library_runtime(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("pub struct DynamicLibrary"));
+    assert!(bindings.contains("pub add: unsafe extern \"C\" fn("));
+    assert!(bindings.contains("pub reset: unsafe extern \"C\" fn()"));
+    assert!(bindings.contains("pub unsafe fn add(&self, a: ::std::os::raw::c_int"));
+    assert!(bindings.contains("pub unsafe fn reset(&self)"));
+    assert!(bindings.contains("library.get::<"));
+    assert!(!bindings.contains("pub fn add("));
+
+    let result = bindgen::builder()
+        .header("tests/headers/func_dynamic.h")
+        .dynamic_library_runtime(true)
+        .use_core_ffi(true)
+        .generate();
+    match result {
+        Err(bindgen::BindgenError::InvalidOptions(msg)) => assert!(msg.contains("use_core_ffi")),
+        _ => panic!("expected the options to be rejected"),
+    }
+}
+
+#[test]
+fn dynamic_library_runtime_cxx_methods() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_overloads.hpp")
+        .generate_cxx_methods(true)
+        .dynamic_library_runtime(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("pub Shape_scale: unsafe extern \"C\" fn("));
+    assert!(bindings.contains("pub Shape_scale1: unsafe extern \"C\" fn("));
+    assert!(bindings.contains("b\"_ZN5Shape5scaleEi"));
+    assert!(bindings.contains("b\"_ZN5Shape5scaleEd"));
+    assert!(!bindings.contains("b\"Shape_scale"));
+}
+
+#[test]
+fn link_names() {
+    assert_bind_eq(Default::default(), "headers/func_link_name.h", "
+        extern \"C\" {
+            pub fn foo() -> ::std::os::raw::c_int;
+            #[link_name = \"type\"]
+            pub fn _type() -> ::std::os::raw::c_int;
+        }
+    ");
+
+    let mut options: BindgenOptions = Default::default();
+    options.raw_link_names = true;
+    assert_bind_eq(options, "headers/func_link_name.h", "
+        extern \"C\" {
+            #[link_name = \"\\u{1}foo\"]
+            pub fn foo() -> ::std::os::raw::c_int;
+            #[link_name = \"\\u{1}type\"]
+            pub fn _type() -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn without_functions() {
+    let mut options: BindgenOptions = Default::default();
+    options.functions = false;
+    assert_bind_eq(options, "headers/func_returning_struct.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct point {
+            pub x: ::std::os::raw::c_int,
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for point {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_function_attributes() {
+    let mut options: BindgenOptions = Default::default();
+    options.emit_function_attributes = true;
+    assert_bind_eq(options, "headers/func_attributes.h", "
+        extern \"C\" {
+            pub fn die(msg: *const ::std::os::raw::c_char) -> !;
+            /// `__attribute__((const))`: only depends on its arguments.
+            pub fn square(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+            /// `__attribute__((pure))`: has no side effects.
+            pub fn length(s: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn with_function_attributes_stripped_comments() {
+    let mut options: BindgenOptions = Default::default();
+    options.emit_function_attributes = true;
+    options.strip_comments = true;
+    assert_bind_eq(options, "headers/func_attributes.h", "
+        extern \"C\" {
+            pub fn die(msg: *const ::std::os::raw::c_char) -> !;
+            pub fn square(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+            pub fn length(s: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn with_link_prefix() {
+    let mut options: BindgenOptions = Default::default();
+    options.link_prefix = "_".to_owned();
+    assert_bind_eq(options, "headers/func_link_name.h", "
+        extern \"C\" {
+            #[link_name = \"_foo\"]
+            pub fn foo() -> ::std::os::raw::c_int;
+            #[link_name = \"_type\"]
+            pub fn _type() -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn with_strip_prefix() {
+    let mut options: BindgenOptions = Default::default();
+    options.strip_prefix = "mylib_".to_owned();
+    assert_bind_eq(options, "headers/func_prefixed.h", "
+        extern \"C\" {
+            #[link_name = \"mylib_errno\"]
+            pub static mut errno: ::std::os::raw::c_int;
+        }
+        extern \"C\" {
+            #[link_name = \"mylib_open\"]
+            pub fn open(path: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+        }
+    ");
+}
+
+#[test]
+fn with_gnu_qualifiers() {
+    let expected = "
+        extern \"C\" {
+            pub fn copy(dst: *mut ::std::os::raw::c_char,
+                        src: *const ::std::os::raw::c_char);
+            pub fn peek(p: *const ::std::os::raw::c_int);
+            pub fn poke(p: *mut ::std::os::raw::c_int);
+        }
+    ";
+    assert_bind_eq(Default::default(), "headers/func_qualifiers.h", expected);
+    assert_bind_eq(Default::default(), "headers/func_gnu_qualifiers.h", expected);
+}
+
+#[test]
+fn with_prelude() {
+    let mut options: BindgenOptions = Default::default();
+    options.emit_prelude = true;
+    assert_bind_eq(options, "headers/func_with_func_ptr_arg.h", "
+        #[allow(unused_imports)]
+        use ::std::option::Option;
+        #[allow(unused_imports)]
+        use ::std::os::raw::*;
+        pub type ty = extern \"C\" fn() -> c_int;
+        extern \"C\" {
+            pub fn foo(bar: Option<extern \"C\" fn()>);
+            pub fn function(_proc: *mut ty);
+        }
+    ");
+}
+
+#[test]
+fn with_function_prefix_module() {
+    let mut options: BindgenOptions = Default::default();
+    options.function_prefix_modules.push(("gl_".to_owned(), "gl".to_owned()));
+    assert_bind_eq(options, "headers/func_prefixed_module.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct gl_color {
+            pub r: ::std::os::raw::c_float,
+            pub g: ::std::os::raw::c_float,
+            pub b: ::std::os::raw::c_float,
+        }
+        impl ::std::default::Default for gl_color {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub fn version() -> ::std::os::raw::c_int;
+        }
+        pub mod gl {
+            #[allow(unused_imports)]
+            use super::*;
+            extern \"C\" {
+                #[link_name = \"gl_clear\"]
+                pub fn clear(color: gl_color);
+                #[link_name = \"gl_flush\"]
+                pub fn flush();
+            }
+        }
+    ");
+}
+
+#[test]
+fn with_cxx_methods() {
+    let mut options: BindgenOptions = Default::default();
+    options.cxx_methods = true;
+    assert_bind_eq(options, "headers/struct_with_methods.hpp", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Counter {
+            pub count: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Counter {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            #[link_name = \"_ZNK7Counter3getEv\"]
+            pub fn Counter_get(this: *const Counter) -> ::std::os::raw::c_int;
+            #[link_name = \"_ZN7Counter3addEi\"]
+            pub fn Counter_add(this: *mut Counter, n: ::std::os::raw::c_int);
+            #[link_name = \"_ZN7Counter4makeEi\"]
+            pub fn Counter_make(count: ::std::os::raw::c_int) -> Counter;
+        }
+    ");
+}
+
+#[test]
+fn with_cxx_overloads() {
+    let mut options: BindgenOptions = Default::default();
+    options.cxx_methods = true;
+    assert_bind_eq(options, "headers/struct_with_overloads.hpp", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Shape {
+            pub sides: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Shape {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            #[link_name = \"_ZN5Shape5scaleEi\"]
+            pub fn Shape_scale(this: *mut Shape, factor: ::std::os::raw::c_int);
+            #[link_name = \"_ZN5Shape5scaleEd\"]
+            pub fn Shape_scale1(this: *mut Shape, factor: ::std::os::raw::c_double);
+        }
+    ");
+}
+
+#[test]
+fn with_doc_alias() {
+    let mut options: BindgenOptions = Default::default();
+    options.strip_prefix = "mylib_".to_owned();
+    options.emit_doc_alias = true;
+    assert_bind_eq(options, "headers/func_prefixed.h", "
+        extern \"C\" {
+            #[doc(alias = \"mylib_errno\")]
+            #[link_name = \"mylib_errno\"]
+            pub static mut errno: ::std::os::raw::c_int;
+        }
+        extern \"C\" {
+            #[doc(alias = \"mylib_open\")]
+            #[link_name = \"mylib_open\"]
+            pub fn open(path: *const ::std::os::raw::c_char) -> ::std::os::raw::c_int;
+        }
+    ");
+}
@@ -0,0 +1,79 @@
+use bindgen;
+use bindgen::BindgenOptions;
+use support::{CollectingLogger, assert_bind_eq};
+
+#[test]
+fn alias_defines() {
+    let mut options: BindgenOptions = Default::default();
+    options.alias_defines = true;
+    assert_bind_eq(options, "headers/macro_alias.h", "
+        pub type real_type = ::std::os::raw::c_int;
+        extern \"C\" {
+            pub fn real_func(x: ::std::os::raw::c_int) -> ::std::os::raw::c_int;
+        }
+        pub use self::real_func as MYFUNC;
+        pub type MYTYPE = real_type;
+    ");
+}
+
+#[test]
+fn alias_defines_continued_line() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/macro_alias.h")
+        .alias_defines(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("MYFUNC"));
+    assert!(!bindings.contains("CONTINUED_ALIAS"));
+}
+
+#[test]
+fn alias_defines_missing_target() {
+    let logger = CollectingLogger::new();
+    assert!(bindgen::builder()
+        .header("tests/headers/macro_alias.h")
+        .alias_defines(true)
+        .log(&logger)
+        .generate()
+        .is_ok());
+    assert!(logger.warnings.borrow().iter().any(|w| w.contains("nothing_here")));
+}
+
+#[test]
+fn macro_constants() {
+    let mut options: BindgenOptions = Default::default();
+    options.generate_macro_constants = true;
+    assert_bind_eq(options, "headers/macro_constants.h", "
+        pub const MAX_PATH: ::std::os::raw::c_int = 260;
+        pub const VERSION_STRING: &'static [u8] = b\"1.2.3\\0\";
+        pub const RATIO: ::std::os::raw::c_double = 0.5;
+        pub const SEPARATOR: ::std::os::raw::c_char = 44u8 as ::std::os::raw::c_char;
+        pub const HEX_MASK: ::std::os::raw::c_int = 255;
+        pub const BIG: ::std::os::raw::c_longlong = 4294967296;
+    ");
+}
+
+#[test]
+fn macro_constants_continued_line() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/macro_constants.h")
+        .generate_macro_constants(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("MAX_PATH"));
+    assert!(!bindings.contains("CONTINUED"));
+}
+
+#[test]
+fn macro_constants_past_i64() {
+    let mut options: BindgenOptions = Default::default();
+    options.generate_macro_constants = true;
+    assert_bind_eq(options, "headers/macro_large_values.h", "
+        pub const ALL_BITS: ::std::os::raw::c_uint = 4294967295;
+        pub const ALL_BITS_64: ::std::os::raw::c_ulonglong = 18446744073709551615;
+    ");
+}
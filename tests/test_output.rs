@@ -0,0 +1,404 @@
+use std::env;
+use std::fs::File;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+
+use bindgen;
+use bindgen::LinkType;
+
+fn generate_with_hash(header: &str) -> String {
+    bindgen::builder()
+        .header(header)
+        .emit_generated_hash(true)
+        .generate()
+        .unwrap()
+        .to_string()
+}
+
+fn input_hash_line(bindings: &str) -> String {
+    bindings.lines().find(|l| l.starts_with("/* bindgen input hash: ")).unwrap().to_owned()
+}
+
+#[test]
+fn generated_hash() {
+    let header = env::temp_dir().join("bindgen_generated_hash.h");
+    let header = header.to_str().unwrap();
+
+    File::create(header).unwrap().write_all(b"int foo;\n").unwrap();
+    let first = input_hash_line(&generate_with_hash(header));
+    assert_eq!(first, input_hash_line(&generate_with_hash(header)));
+
+    File::create(header).unwrap().write_all(b"int bar;\n").unwrap();
+    assert!(first != input_hash_line(&generate_with_hash(header)));
+
+    let bindings = bindgen::builder().header(header).generate().unwrap().to_string();
+    assert!(!bindings.contains("bindgen input hash"));
+}
+
+#[test]
+fn block_comments() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .generate_block_comments(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    let types = bindings.find("// --- types ---").unwrap();
+    let variables = bindings.find("// --- variables ---").unwrap();
+    let functions = bindings.find("// --- functions ---").unwrap();
+    assert!(types < bindings.find("pub struct point").unwrap());
+    assert!(types < variables && variables < functions);
+    assert_eq!(bindings.matches("// --- types ---").count(), 1);
+}
+
+#[test]
+fn append_to_file() {
+    let path = env::temp_dir().join("bindgen_append_to_file.rs");
+    File::create(&path)
+        .unwrap()
+        .write_all(b"// hand-written\npub fn helper() {}\n// BINDGEN-END\npub fn after() {}\n")
+        .unwrap();
+
+    bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .generate()
+        .unwrap()
+        .append_to_file(&path)
+        .unwrap();
+
+    let mut contents = String::new();
+    File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+    assert!(contents.starts_with("// hand-written\npub fn helper() {}\n"));
+    assert!(contents.ends_with("// BINDGEN-END\npub fn after() {}\n"));
+    let generated = contents.find("pub struct point").unwrap();
+    assert!(contents.find("pub fn helper").unwrap() < generated);
+    assert!(generated < contents.find("// BINDGEN-END").unwrap());
+    assert!(!contents.contains("#!["));
+}
+
+#[test]
+fn append_to_file_compiles() {
+    let path = env::temp_dir().join("bindgen_append_to_file_compiles.rs");
+    File::create(&path)
+        .unwrap()
+        .write_all(b"#![allow(dead_code)]\npub fn helper() {}\n// BINDGEN-END\n")
+        .unwrap();
+
+    bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .generate()
+        .unwrap()
+        .append_to_file(&path)
+        .unwrap();
+
+    let mut contents = String::new();
+    File::open(&path).unwrap().read_to_string(&mut contents).unwrap();
+    assert!(run_with_bindings("bindgen_append_to_file_compiles", &contents, "fn main() {}"));
+}
+
+#[test]
+fn crate_attributes() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .use_core_ffi(true)
+        .emit_crate_attributes(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    let no_std = bindings.find("#![no_std]").unwrap();
+    assert!(no_std < bindings.find("#![allow(").unwrap());
+    assert!(bindings.contains("::core::ffi::c_int"));
+    assert!(!bindings.contains("::std::"));
+
+    let bindings = bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .use_core_ffi(true)
+        .generate()
+        .unwrap()
+        .to_string();
+    assert!(!bindings.contains("#![no_std]"));
+}
+
+#[test]
+fn matched_source_files() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/source_files.h")
+        .match_pat("source_files_included.h")
+        .record_matched_files(true)
+        .generate()
+        .unwrap();
+
+    let files = bindings.source_files();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with("source_files_included.h"));
+    assert!(bindings.to_string().contains("in_included"));
+    assert!(!bindings.to_string().contains("in_main"));
+}
+
+#[test]
+fn source_files_of_emitted_items() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/source_files.h")
+        .whitelist_var("in_included")
+        .record_matched_files(true)
+        .generate()
+        .unwrap();
+
+    let files = bindings.source_files();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with("source_files_included.h"));
+
+    let bindings = bindgen::builder()
+        .header("tests/headers/source_files.h")
+        .emit_globals(false)
+        .record_matched_files(true)
+        .generate()
+        .unwrap();
+    assert!(bindings.source_files().is_empty());
+}
+
+#[test]
+fn strip_comments_from_output() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_renamed.h")
+        .generate_block_comments(true)
+        .emit_original_name_comment(true)
+        .strip_comments_from_output(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    let mut lines = bindings.lines();
+    assert_eq!(lines.next(), Some("/* automatically generated by rust-bindgen */"));
+    assert!(lines.all(|l| !l.contains("//") && !l.contains("/*")));
+    assert!(bindings.contains("pub struct str_"));
+}
+
+#[test]
+fn cargo_link_directives() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/block_comments.h")
+        .link("foo", LinkType::Static)
+        .link("bar", LinkType::Dynamic)
+        .link("CoreFoundation", LinkType::Framework)
+        .generate()
+        .unwrap();
+
+    let mut out = Vec::new();
+    bindings.write_cargo_link_directives(&mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(),
+               "cargo:rustc-link-lib=static=foo\n\
+                cargo:rustc-link-lib=dylib=bar\n\
+                cargo:rustc-link-lib=framework=CoreFoundation\n");
+}
+
+#[test]
+fn several_headers() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/multi_header_segment.h")
+        .header("tests/headers/multi_header_point.h")
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert_eq!(bindings.matches("pub struct point").count(), 1);
+    assert!(bindings.find("pub struct point").unwrap() <
+            bindings.find("pub struct segment").unwrap());
+}
+
+#[test]
+fn several_headers_cxx() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/multi_header_point.h")
+        .header("tests/headers/multi_header_flag.hpp")
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("pub struct point"));
+    assert!(bindings.contains("pub set: bool"));
+}
+
+#[test]
+fn generated_hash_of_stdin() {
+    // The test runs itself again with a header on its standard input.
+    if env::var("BINDGEN_TEST_STDIN").is_ok() {
+        let bindings = bindgen::builder()
+            .header("tests/headers/multi_header_point.h")
+            .header("-")
+            .emit_generated_hash(true)
+            .generate()
+            .unwrap()
+            .to_string();
+        println!("{}", bindings);
+        return;
+    }
+
+    let run = |header: &str| {
+        let mut child = Command::new(env::current_exe().unwrap())
+                            .args(&["generated_hash_of_stdin", "--nocapture"])
+                            .env("BINDGEN_TEST_STDIN", "1")
+                            .stdin(Stdio::piped())
+                            .stdout(Stdio::piped())
+                            .spawn()
+                            .unwrap();
+        child.stdin.take().unwrap().write_all(header.as_bytes()).unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        assert!(stdout.contains("pub struct point"));
+        input_hash_line(&stdout)
+    };
+
+    let first = run("int foo;\n");
+    assert_eq!(first, run("int foo;\n"));
+    assert!(first != run("int bar;\n"));
+}
+
+#[test]
+fn precompiled_header() {
+    let pch = env::temp_dir().join("bindgen_pch_types.h.pch");
+    let made = Command::new("clang")
+                   .args(&["-x", "c-header", "tests/headers/pch_types.h", "-o"])
+                   .arg(&pch)
+                   .status()
+                   .map(|status| status.success())
+                   .unwrap_or(false);
+    if !made {
+        // No clang to make the precompiled header with.
+        return;
+    }
+
+    let bindings = match bindgen::builder()
+                             .header("tests/headers/pch_main.h")
+                             .pch(pch.to_str().unwrap())
+                             .match_pat("pch_")
+                             .generate() {
+        Ok(bindings) => bindings.to_string(),
+        // The clang binary and libclang don't read the same PCH format.
+        Err(bindgen::BindgenError::ParseFailed(ref msg)) if msg.contains("PCH") => return,
+        Err(e) => panic!("{}", e),
+    };
+
+    assert!(bindings.contains("pub struct pch_point"));
+    assert!(bindings.contains("pub fn pch_origin() -> pch_point;"));
+}
+
+#[test]
+fn conditional_raw_lines() {
+    let generate = |features: &[&str]| {
+        let mut builder = bindgen::builder();
+        builder.header("tests/headers/block_comments.h")
+               .raw_line("use std::ptr;")
+               .raw_line_if("libc", "extern crate libc;");
+        for feature in features {
+            builder.feature(*feature);
+        }
+        builder.generate().unwrap().to_string()
+    };
+
+    let without = generate(&[]);
+    assert!(without.contains("use std::ptr;\n"));
+    assert!(!without.contains("extern crate libc;"));
+
+    let with = generate(&["libc"]);
+    assert!(with.contains("use std::ptr;\nextern crate libc;\n"));
+    assert!(with.find("#![allow(").unwrap() < with.find("use std::ptr;").unwrap());
+    assert!(run_with_bindings("bindgen_conditional_raw_lines",
+                              &without,
+                              "fn main() { let _ = ptr::null::<point>(); }"));
+}
+
+/// Compiles the bindings with the given `main` and runs the program, returning
+/// whether it succeeded.
+fn run_with_bindings(name: &str, bindings: &str, main: &str) -> bool {
+    let dir = env::temp_dir();
+    let source = dir.join(format!("{}.rs", name));
+    let binary = dir.join(name);
+    File::create(&source)
+        .unwrap()
+        .write_all(format!("{}\n{}\n", bindings, main).as_bytes())
+        .unwrap();
+
+    let compiled = Command::new("rustc")
+        .arg("-o")
+        .arg(&binary)
+        .arg(&source)
+        .status()
+        .unwrap();
+    compiled.success() && Command::new(&binary).status().unwrap().success()
+}
+
+#[test]
+fn array_newtype_index_bounds() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/typedef_array_vec4.h")
+        .array_newtype_indexing(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(run_with_bindings("bindgen_array_newtype_index", &bindings, "
+        fn main() {
+            let mut v = vec4([0.0; 4]);
+            v[3] = 1.5;
+            assert_eq!(v[3], 1.5);
+            assert_eq!(v.0[3], 1.5);
+            let out_of_bounds = ::std::panic::catch_unwind(move || v[4]);
+            assert!(out_of_bounds.is_err());
+        }
+    "));
+}
+
+#[test]
+fn manual_hash_floats() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_floats.h")
+        .derive("PartialEq")
+        .derive("Hash")
+        .manual_hash_floats(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(run_with_bindings("bindgen_manual_hash_floats", &bindings, "
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash_of(s: &sample) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            s.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        fn main() {
+            let a = sample { id: 1, weight: 2.5, coords: [1.0, 2.0, 3.0] };
+            let b = sample { id: 1, weight: 2.5, coords: [1.0, 2.0, 3.0] };
+            let c = sample { id: 1, weight: 2.5, coords: [1.0, 2.0, 4.0] };
+            assert_eq!(hash_of(&a), hash_of(&b));
+            assert!(hash_of(&a) != hash_of(&c));
+            let zero = sample { id: 1, weight: 0.0, coords: [0.0, 0.0, 0.0] };
+            let negative_zero = sample { id: 1, weight: -0.0, coords: [-0.0, 0.0, -0.0] };
+            assert!(zero == negative_zero);
+            assert_eq!(hash_of(&zero), hash_of(&negative_zero));
+        }
+    "));
+}
+
+#[test]
+fn wasm32_target_env() {
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_long_and_pointer.h")
+        .for_wasm32()
+        .emit_layout_constants(true)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    // `long` and the pointers are 32 bits on wasm32.
+    assert!(bindings.contains("pub value: i32,"));
+    assert!(bindings.contains("pub const SIZE: usize = 8;"));
+    assert!(bindings.contains("pub const ALIGN: usize = 4;"));
+}
@@ -1,6 +1,8 @@
 use std::mem;
 
-use support::assert_bind_eq;
+use bindgen;
+use bindgen::BindgenOptions;
+use support::{CollectingLogger, assert_bind_eq};
 
 #[test]
 fn with_anon_enum() {
@@ -159,7 +161,6 @@ fn with_anon_union() {
         }
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct Union_Unnamed1 {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -173,6 +174,15 @@ fn with_anon_union() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for Union_Unnamed1 {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"Union_Unnamed1\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for Union_Unnamed1 {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -229,6 +239,38 @@ fn with_anon_unnamed_union() {
     ");
 }
 
+#[test]
+fn with_large_anon_unnamed_struct() {
+    assert_bind_eq(Default::default(), "headers/struct_with_large_anon_unnamed_struct.h", "
+        #[repr(C)]
+        #[derive(Copy)]
+        pub struct foo {
+            pub x: ::std::os::raw::c_int,
+            pub _bindgen_data_1_: [u32; 40usize],
+        }
+        impl foo {
+            pub unsafe fn values(&mut self) -> *mut [::std::os::raw::c_int; 40usize] {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_1_);
+                ::std::mem::transmute(raw.offset(0))
+            }
+        }
+        impl ::std::clone::Clone for foo {
+            fn clone(&self) -> Self { *self }
+        }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                f.debug_struct(\"foo\")
+                 .field(\"x\", &self.x)
+                 .field(\"_bindgen_data_1_\", &&self._bindgen_data_1_[..])
+                 .finish()
+            }
+        }
+        impl ::std::default::Default for foo {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
 #[test]
 fn with_nesting() {
     assert_bind_eq(Default::default(), "headers/struct_with_nesting.h", "
@@ -450,7 +492,8 @@ fn struct_with_aligned_struct() {
             pub x: int32_t,
             pub y: int64_t,
             pub z: int16_t,
-            _bindgen_padding_0_: [u64; 5usize],
+            _bindgen_padding_0_: [u8; 32usize],
+            _bindgen_padding_1_: [u8; 14usize],
         }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
@@ -479,7 +522,8 @@ fn struct_with_aligned_struct() {
         pub x: int32_t,
         pub y: int64_t,
         pub z: int16_t,
-        _bindgen_padding_0_: [u64; 5usize],
+        _bindgen_padding_0_: [u8; 32usize],
+        _bindgen_padding_1_: [u8; 14usize],
     }
 
     #[repr(C)]
@@ -496,7 +540,8 @@ fn struct_with_aligned_struct() {
     assert_eq!(offset_of!(foo, x), 0);
     assert_eq!(offset_of!(foo, y), 8);
     assert_eq!(offset_of!(foo, z), 16);
-    assert_eq!(offset_of!(foo, _bindgen_padding_0_), 24);
+    assert_eq!(offset_of!(foo, _bindgen_padding_0_), 18);
+    assert_eq!(offset_of!(foo, _bindgen_padding_1_), 50);
 
     assert_eq!(mem::size_of::<bar>(), 128);
     assert_eq!(offset_of!(bar, a), 0);
@@ -504,3 +549,1043 @@ fn struct_with_aligned_struct() {
     assert_eq!(offset_of!(bar, _bindgen_padding_0_), 16);
     assert_eq!(offset_of!(bar, foo), 64);
 }
+
+#[test]
+fn with_wide_chars() {
+    let wchar = if cfg!(windows) {
+        "u16"
+    } else if cfg!(all(any(target_arch = "arm", target_arch = "aarch64"),
+                       not(target_os = "macos"))) {
+        "u32"
+    } else {
+        "i32"
+    };
+    assert_bind_eq(Default::default(), "headers/struct_with_wide_chars.hpp", &format!("
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct foo {{
+            pub a: {},
+            pub b: u16,
+            pub c: u32,
+        }}
+        impl ::std::default::Default for foo {{
+            fn default() -> Self {{ unsafe {{ ::std::mem::zeroed() }} }}
+        }}
+    ", wchar));
+}
+
+#[test]
+fn with_wide_chars_target_signedness() {
+    let wchar_field = |triple: &str| {
+        let bindings = bindgen::builder()
+            .header("tests/headers/struct_with_wide_chars.hpp")
+            .clang_target_env(triple)
+            .generate()
+            .unwrap()
+            .to_string();
+        bindings.lines()
+                .find(|l| l.contains("pub a:"))
+                .unwrap()
+                .trim()
+                .to_owned()
+    };
+
+    assert_eq!(wchar_field("x86_64-unknown-linux-gnu"), "pub a: i32,");
+    assert_eq!(wchar_field("aarch64-unknown-linux-gnu"), "pub a: u32,");
+    assert_eq!(wchar_field("x86_64-pc-windows-msvc"), "pub a: u16,");
+}
+
+#[test]
+#[cfg(not(windows))]
+fn with_wide_chars_as_u32() {
+    assert_bind_eq(BindgenOptions { wchar_as_u32: true, .. Default::default() },
+                   "headers/struct_with_wide_chars.hpp", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct foo {
+            pub a: u32,
+            pub b: u16,
+            pub c: u32,
+        }
+        impl ::std::default::Default for foo {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn pod_byte_helpers() {
+    #![allow(non_camel_case_types)]
+
+    let mut options: BindgenOptions = Default::default();
+    options.pod_byte_helpers = vec!["pod".to_owned(), "not_pod".to_owned()];
+    assert_bind_eq(options, "headers/struct_pod.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct pod {
+            pub a: ::std::os::raw::c_int,
+            pub b: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for pod {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        impl pod {
+            pub fn as_bytes(&self) -> &[u8] {
+                unsafe { ::std::slice::from_raw_parts(self as *const Self as *const u8, 8) }
+            }
+            pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {
+                assert!(bytes.len() >= 8);
+                assert!(bytes.as_ptr() as usize % ::std::mem::align_of::<Self>() == 0);
+                &*(bytes.as_ptr() as *const Self)
+            }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct not_pod {
+            pub p: *mut ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for not_pod {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+
+    #[repr(C)]
+    #[derive(Copy, Clone)]
+    #[derive(Debug)]
+    pub struct pod {
+        pub a: ::std::os::raw::c_int,
+        pub b: ::std::os::raw::c_int,
+    }
+    impl pod {
+        pub fn as_bytes(&self) -> &[u8] {
+            unsafe { ::std::slice::from_raw_parts(self as *const Self as *const u8, 8) }
+        }
+        pub unsafe fn from_bytes(bytes: &[u8]) -> &Self {
+            assert!(bytes.len() >= 8);
+            assert!(bytes.as_ptr() as usize % ::std::mem::align_of::<Self>() == 0);
+            &*(bytes.as_ptr() as *const Self)
+        }
+    }
+
+    let p = pod { a: 1, b: -2 };
+    let q = unsafe { pod::from_bytes(p.as_bytes()) };
+    assert_eq!(q.a, 1);
+    assert_eq!(q.b, -2);
+
+    let buffer = [0u32; 4];
+    let bytes = unsafe { ::std::slice::from_raw_parts(buffer.as_ptr() as *const u8, 16) };
+    let unaligned = ::std::panic::catch_unwind(|| unsafe { pod::from_bytes(&bytes[1..]).a });
+    assert!(unaligned.is_err());
+}
+
+#[test]
+fn with_implicit_int() {
+    assert_bind_eq(Default::default(), "headers/struct_with_implicit_int.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct foo {
+            pub x: ::std::os::raw::c_uint,
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for foo {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_no_copy() {
+    let mut options: BindgenOptions = Default::default();
+    options.no_copy = vec!["Handle".to_owned()];
+    assert_bind_eq(options, "headers/struct_with_no_copy.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Copyable {
+            pub a: ::std::os::raw::c_int,
+            pub cb: ::std::option::Option<extern \"C\" fn(arg1: ::std::os::raw::c_int)>,
+        }
+        impl ::std::default::Default for Copyable {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Clone)]
+        #[derive(Debug)]
+        pub struct Handle {
+            pub fd: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Handle {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Clone)]
+        #[derive(Debug)]
+        pub struct WithHandle {
+            pub h: Handle,
+        }
+        impl ::std::default::Default for WithHandle {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy)]
+        pub struct WithArray {
+            pub a: [::std::os::raw::c_int; 33usize],
+        }
+        impl ::std::clone::Clone for WithArray {
+            fn clone(&self) -> Self { *self }
+        }
+        impl ::std::default::Default for WithArray {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn warnings_as_errors() {
+    let header = "tests/headers/struct_with_anon_enum_bitfields.h";
+    assert!(bindgen::builder().header(header).generate().is_ok());
+    assert!(bindgen::builder().header(header).treat_warnings_as_errors(true).generate().is_err());
+}
+
+#[test]
+fn with_nested_layout_tests() {
+    let mut options: BindgenOptions = Default::default();
+    options.layout_offset_tests = true;
+    assert_bind_eq(options, "headers/struct_with_nested_layout.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct inner {
+            pub a: ::std::os::raw::c_char,
+            pub b: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for inner {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[test]
+        fn bindgen_test_layout_inner() {
+            assert_eq!(::std::mem::size_of::<inner>(), 8usize);
+            assert_eq!(::std::mem::align_of::<inner>(), 4usize);
+            assert_eq!(unsafe { &(*(0 as *const inner)).a as *const _ as usize }, 0usize);
+            assert_eq!(unsafe { &(*(0 as *const inner)).b as *const _ as usize }, 4usize);
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct outer {
+            pub c: ::std::os::raw::c_char,
+            pub i: inner,
+            pub d: f64,
+        }
+        impl ::std::default::Default for outer {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[test]
+        fn bindgen_test_layout_outer() {
+            assert_eq!(::std::mem::size_of::<outer>(), 24usize);
+            assert_eq!(::std::mem::align_of::<outer>(), 8usize);
+            assert_eq!(unsafe { &(*(0 as *const outer)).c as *const _ as usize }, 0usize);
+            assert_eq!(unsafe { &(*(0 as *const outer)).i as *const _ as usize }, 4usize);
+            assert_eq!(unsafe { &(*(0 as *const outer)).d as *const _ as usize }, 16usize);
+        }
+    ");
+}
+
+#[test]
+fn with_field_visibility() {
+    let mut options: BindgenOptions = Default::default();
+    options.field_visibility = bindgen::Visibility::PublicCrate;
+    options.field_visibility_overrides = vec![("Point::y".to_owned(), bindgen::Visibility::Private),
+                                              ("Open".to_owned(), bindgen::Visibility::Public)];
+    assert_bind_eq(options, "headers/struct_with_field_visibility.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Point {
+            pub(crate) x: ::std::os::raw::c_int,
+            y: ::std::os::raw::c_int,
+        }
+        impl Point {
+            pub fn get_x(&self) -> &::std::os::raw::c_int {
+                &self.x
+            }
+            pub fn get_y(&self) -> &::std::os::raw::c_int {
+                &self.y
+            }
+        }
+        impl ::std::default::Default for Point {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Open {
+            pub a: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Open {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_c_naming() {
+    let mut options: BindgenOptions = Default::default();
+    options.c_naming = true;
+    assert_bind_eq(options, "headers/struct_with_c_naming.h", "
+        pub type u32 = ::std::os::raw::c_uint;
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct str {
+            pub _type: u32,
+            pub bool: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for str {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_float16() {
+    let mut options: BindgenOptions = Default::default();
+    options.layout_offset_tests = true;
+    assert_bind_eq(options, "headers/struct_with_float16.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone, Debug, Default, PartialEq)]
+        pub struct __BindgenFloat16(pub u16);
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct half_pair {
+            pub a: __BindgenFloat16,
+            pub b: __BindgenFloat16,
+        }
+        impl ::std::default::Default for half_pair {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[test]
+        fn bindgen_test_layout_half_pair() {
+            assert_eq!(::std::mem::size_of::<half_pair>(), 4usize);
+            assert_eq!(::std::mem::align_of::<half_pair>(), 2usize);
+            assert_eq!(unsafe { &(*(0 as *const half_pair)).a as *const _ as usize }, 0usize);
+            assert_eq!(unsafe { &(*(0 as *const half_pair)).b as *const _ as usize }, 2usize);
+        }
+    ");
+}
+
+#[test]
+fn with_slice_accessors() {
+    let mut options: BindgenOptions = Default::default();
+    options.slice_accessors = vec![("list".to_owned(), "items".to_owned(), "count".to_owned())];
+    assert_bind_eq(options, "headers/struct_with_slice.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct list {
+            pub items: *mut ::std::os::raw::c_int,
+            pub count: ::std::os::raw::c_ulong,
+        }
+        impl list {
+            pub unsafe fn items_slice(&self) -> &[::std::os::raw::c_int] {
+                if self.items.is_null() {
+                    &[]
+                } else {
+                    ::std::slice::from_raw_parts(self.items, self.count as usize)
+                }
+            }
+        }
+        impl ::std::default::Default for list {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_track_caller() {
+    let mut options: BindgenOptions = Default::default();
+    options.slice_accessors = vec![("list".to_owned(), "items".to_owned(), "count".to_owned())];
+    options.emit_track_caller = true;
+    assert_bind_eq(options, "headers/struct_with_slice.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct list {
+            pub items: *mut ::std::os::raw::c_int,
+            pub count: ::std::os::raw::c_ulong,
+        }
+        impl list {
+            #[track_caller]
+            pub unsafe fn items_slice(&self) -> &[::std::os::raw::c_int] {
+                if self.items.is_null() {
+                    &[]
+                } else {
+                    ::std::slice::from_raw_parts(self.items, self.count as usize)
+                }
+            }
+        }
+        impl ::std::default::Default for list {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_over_aligned_field() {
+    let mut options: BindgenOptions = Default::default();
+    options.layout_offset_tests = true;
+    assert_bind_eq(options, "headers/struct_with_over_aligned_field.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct over_aligned {
+            pub a: ::std::os::raw::c_char,
+            _bindgen_padding_0_: [u8; 15usize],
+            pub b: ::std::os::raw::c_int,
+            _bindgen_padding_1_: [u8; 12usize],
+        }
+        impl ::std::default::Default for over_aligned {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[test]
+        fn bindgen_test_layout_over_aligned() {
+            assert_eq!(::std::mem::size_of::<over_aligned>(), 32usize);
+            assert_eq!(unsafe { &(*(0 as *const over_aligned)).a as *const _ as usize }, 0usize);
+            assert_eq!(unsafe { &(*(0 as *const over_aligned)).b as *const _ as usize }, 16usize);
+        }
+    ");
+}
+
+#[test]
+fn with_original_name_comment() {
+    let mut options: BindgenOptions = Default::default();
+    options.emit_original_name_comment = true;
+    assert_bind_eq(options, "headers/struct_with_renamed.h", "
+        /// originally: str
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct _str {
+            /// originally: type
+            pub _type: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for _str {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_anon_field_prefix() {
+    let mut options: BindgenOptions = Default::default();
+    options.anon_field_prefix = "_anon".to_owned();
+    assert_bind_eq(options, "headers/struct_with_anon_unnamed_struct.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct foo {
+            pub _anon1_: [u32; 2usize],
+        }
+        impl foo {
+            pub unsafe fn a(&mut self) -> *mut ::std::os::raw::c_uint {
+                let raw: *mut u8 = ::std::mem::transmute(&self._anon1_);
+                ::std::mem::transmute(raw.offset(0))
+            }
+            pub unsafe fn b(&mut self) -> *mut ::std::os::raw::c_uint {
+                let raw: *mut u8 = ::std::mem::transmute(&self._anon1_);
+                ::std::mem::transmute(raw.offset(4))
+            }
+        }
+        impl ::std::default::Default for foo {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_helpers_in_module() {
+    let mut options: BindgenOptions = Default::default();
+    options.helpers_module = Some("detail".to_owned());
+    assert_bind_eq(options, "headers/struct_with_float16.h", "
+        pub(crate) mod detail {
+            #[repr(C)]
+            #[derive(Copy, Clone, Debug, Default, PartialEq)]
+            pub struct __BindgenFloat16(pub u16);
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct half_pair {
+            pub a: detail::__BindgenFloat16,
+            pub b: detail::__BindgenFloat16,
+        }
+        impl ::std::default::Default for half_pair {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_static_const_member() {
+    assert_bind_eq(Default::default(), "headers/struct_with_static_const.hpp", "
+        pub const Limits_max: ::std::os::raw::c_int = 10;
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Limits {
+            pub value: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Limits {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_derives() {
+    let mut options: BindgenOptions = Default::default();
+    options.derives = vec!["PartialEq".to_owned(), "Eq".to_owned(),
+                           "Hash".to_owned(), "Default".to_owned()];
+    assert_bind_eq(options, "headers/struct_with_derives.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        #[derive(PartialEq, Eq, Hash, Default)]
+        pub struct point {
+            pub x: ::std::os::raw::c_int,
+            pub y: ::std::os::raw::c_int,
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        #[derive(Default)]
+        pub struct shape {
+            pub origin: point,
+            pub area: ::std::option::Option<
+                extern \"C\" fn(scale: ::std::os::raw::c_int) -> ::std::os::raw::c_int>,
+        }
+    ");
+}
+
+#[test]
+fn with_unknown_derive() {
+    let logger = CollectingLogger::new();
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_derives.h")
+        .derive("Hash")
+        .derive("Ord")
+        .log(&logger)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(bindings.contains("#[derive(Hash)]"));
+    assert!(!bindings.contains("Ord"));
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w == "`Ord` matches none of the traits which can be derived, \
+                                 PartialEq, Eq, Hash, Default"));
+}
+
+#[test]
+fn with_comments() {
+    let mut options: BindgenOptions = Default::default();
+    options.generate_comments = true;
+    assert_bind_eq(options, "headers/struct_with_comments.h", "
+        /// A point on the screen.
+        ///
+        /// The origin is the top left corner.
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct point {
+            /// The column.
+            pub x: ::std::os::raw::c_int,
+            /// The row.
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for point {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        /// The directions.
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum direction {
+            /// Towards the top.
+            UP = 0,
+            DOWN = 1,
+        }
+        extern \"C\" {
+            /// Moves the point.
+            pub fn move_point(p: *mut point, dir: direction);
+        }
+    ");
+}
+
+fn options_for_target(target: &str, fixed_width_integers: bool) -> BindgenOptions {
+    let mut options: BindgenOptions = Default::default();
+    options.clang_args.push("-target".to_owned());
+    options.clang_args.push(target.to_owned());
+    options.fixed_width_integers = fixed_width_integers;
+    options
+}
+
+#[test]
+fn with_long_by_default() {
+    for target in &["i686-unknown-linux-gnu", "x86_64-unknown-linux-gnu"] {
+        assert_bind_eq(options_for_target(target, false), "headers/struct_with_long.h", "
+            #[repr(C)]
+            #[derive(Copy, Clone)]
+            #[derive(Debug)]
+            pub struct sizes {
+                pub a: ::std::os::raw::c_long,
+                pub b: ::std::os::raw::c_ulong,
+                pub c: ::std::os::raw::c_int,
+                pub d: ::std::os::raw::c_int,
+            }
+            impl ::std::default::Default for sizes {
+                fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+            }
+        ");
+    }
+}
+
+#[test]
+fn with_fixed_width_integers() {
+    let options = options_for_target("i686-unknown-linux-gnu", true);
+    assert_bind_eq(options, "headers/struct_with_long.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct sizes {
+            pub a: i32,
+            pub b: u32,
+            pub c: i32,
+            pub d: i32,
+        }
+        impl ::std::default::Default for sizes {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+
+    let options = options_for_target("x86_64-unknown-linux-gnu", true);
+    assert_bind_eq(options, "headers/struct_with_long.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct sizes {
+            pub a: i64,
+            pub b: u64,
+            pub c: i32,
+            pub d: i32,
+        }
+        impl ::std::default::Default for sizes {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_layout_constants() {
+    let mut options: BindgenOptions = Default::default();
+    options.emit_layout_constants = true;
+    assert_bind_eq(options, "headers/struct_with_layout.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct pair {
+            pub tag: ::std::os::raw::c_char,
+            pub value: ::std::os::raw::c_int,
+        }
+        impl pair {
+            pub const SIZE: usize = 8;
+            pub const ALIGN: usize = 4;
+        }
+        impl ::std::default::Default for pair {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_array_fields() {
+    assert_bind_eq(Default::default(), "headers/struct_with_arrays.h", "
+        #[repr(C)]
+        #[derive(Copy)]
+        pub struct buffers {
+            pub data: [::std::os::raw::c_int; 16usize],
+            pub m: [[::std::os::raw::c_int; 4usize]; 4usize],
+            pub large: [::std::os::raw::c_uchar; 64usize],
+        }
+        impl ::std::clone::Clone for buffers {
+            fn clone(&self) -> Self { *self }
+        }
+        impl ::std::default::Default for buffers {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_opaque_type() {
+    let mut options: BindgenOptions = Default::default();
+    options.opaque_types.push("internal".to_owned());
+    assert_bind_eq(options, "headers/struct_with_opaque.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct internal {
+            pub _bindgen_data_: [u64; 2usize],
+        }
+        impl ::std::default::Default for internal {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct handle {
+            pub inner: internal,
+            pub id: ::std::os::raw::c_int,
+            _bindgen_padding_0_: [u8; 4usize],
+        }
+        impl ::std::default::Default for handle {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_opaque_type_derives() {
+    let mut options: BindgenOptions = Default::default();
+    options.opaque_types.push("internal".to_owned());
+    options.derives = vec!["PartialEq".to_owned(), "Eq".to_owned(),
+                           "Hash".to_owned(), "Default".to_owned()];
+    assert_bind_eq(options, "headers/struct_with_opaque.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct internal {
+            pub _bindgen_data_: [u64; 2usize],
+        }
+        impl ::std::default::Default for internal {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        #[derive(Default)]
+        pub struct handle {
+            pub inner: internal,
+            pub id: ::std::os::raw::c_int,
+            _bindgen_padding_0_: [u8; 4usize],
+        }
+    ");
+}
+
+#[test]
+fn with_maybe_uninit_fields() {
+    let mut options: BindgenOptions = Default::default();
+    options.maybe_uninit_fields = true;
+    assert_bind_eq(options, "headers/struct_with_nonzero_enum.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum state { STATE_ON = 1, STATE_OFF = 2, }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct device {
+            pub id: ::std::os::raw::c_int,
+            pub state: ::std::mem::MaybeUninit<state>,
+        }
+        impl device {
+            pub unsafe fn get_state(&self) -> state {
+                self.state.assume_init()
+            }
+            pub fn set_state(&mut self, value: state) {
+                self.state = ::std::mem::MaybeUninit::new(value);
+            }
+        }
+        impl ::std::default::Default for device {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_base_class() {
+    let base = "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Base {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Base {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct Derived {
+            pub _base: Base,
+            pub y: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for Derived {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ";
+    assert_bind_eq(Default::default(), "headers/struct_with_base.hpp", base);
+
+    let mut options: BindgenOptions = Default::default();
+    options.emit_base_deref = true;
+    assert_bind_eq(options, "headers/struct_with_base.hpp", &format!("{}{}", base, "
+        impl ::std::ops::Deref for Derived {
+            type Target = Base;
+            fn deref(&self) -> &Base { &self._base }
+        }
+        impl ::std::ops::DerefMut for Derived {
+            fn deref_mut(&mut self) -> &mut Base { &mut self._base }
+        }
+    "));
+}
+
+#[test]
+fn with_char_array_as_cstr() {
+    let mut options: BindgenOptions = Default::default();
+    options.cstr_fields.push("user::name".to_owned());
+    assert_bind_eq(options, "headers/struct_with_cstr.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct user {
+            pub name: [::std::os::raw::c_char; 32usize],
+            pub age: ::std::os::raw::c_int,
+        }
+        impl user {
+            pub fn name_cstr(&self) -> &::std::ffi::CStr {
+                let bytes = unsafe {
+                    ::std::slice::from_raw_parts(self.name.as_ptr() as *const u8, 32)
+                };
+                match bytes.iter().position(|&b| b == 0) {
+                    Some(nul) => unsafe {
+                        ::std::ffi::CStr::from_bytes_with_nul_unchecked(&bytes[..nul + 1])
+                    },
+                    None => panic!(\"`name` is not nul-terminated\"),
+                }
+            }
+            pub fn set_name(&mut self, s: &::std::ffi::CStr) {
+                let bytes = s.to_bytes();
+                let len = ::std::cmp::min(bytes.len(), 32 - 1);
+                for (dst, &src) in self.name.iter_mut().zip(&bytes[..len]) {
+                    *dst = src as ::std::os::raw::c_char;
+                }
+                self.name[len] = 0;
+            }
+        }
+        impl ::std::default::Default for user {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_template_instance_typedef() {
+    assert_bind_eq(Default::default(), "headers/typedef_template_instance.hpp", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct IntVec {
+            pub _bindgen_data_: [u64; 2usize],
+        }
+        impl ::std::default::Default for IntVec {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        extern \"C\" {
+            pub fn int_vec_push(v: *mut IntVec, value: ::std::os::raw::c_int);
+        }
+    ");
+}
+
+#[test]
+fn with_uninstantiated_template_typedef() {
+    assert_bind_eq(Default::default(), "headers/typedef_template_uninstantiated.hpp", "
+        pub enum IntVec {  }
+        extern \"C\" {
+            pub fn int_vec_len(v: *const IntVec) -> ::std::os::raw::c_uint;
+        }
+    ");
+}
+
+#[test]
+fn with_manual_hash_floats() {
+    let mut options: BindgenOptions = Default::default();
+    options.derives = vec!["PartialEq".to_owned(), "Hash".to_owned()];
+    options.manual_hash_floats = true;
+    assert_bind_eq(options, "headers/struct_with_floats.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        #[derive(PartialEq)]
+        pub struct sample {
+            pub id: ::std::os::raw::c_int,
+            pub weight: ::std::os::raw::c_float,
+            pub coords: [::std::os::raw::c_double; 3usize],
+        }
+        impl ::std::default::Default for sample {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        impl ::std::hash::Hash for sample {
+            fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {
+                use ::std::hash::Hash;
+                self.id.hash(state);
+                (if self.weight == 0.0 { 0.0 } else { self.weight }).to_bits().hash(state);
+                for &x in self.coords.iter() {
+                    (if x == 0.0 { 0.0 } else { x }).to_bits().hash(state);
+                }
+            }
+        }
+    ");
+}
+
+#[test]
+fn with_deep_anon_nesting() {
+    assert_bind_eq(Default::default(), "headers/struct_with_deep_anon_nesting.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct outer {
+            pub tag: ::std::os::raw::c_char,
+            pub _bindgen_data_1_: [u32; 3usize],
+        }
+        impl outer {
+            pub unsafe fn kind(&mut self) -> *mut ::std::os::raw::c_short {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_1_);
+                ::std::mem::transmute(raw.offset(0))
+            }
+            pub unsafe fn flag(&mut self) -> *mut ::std::os::raw::c_char {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_1_);
+                ::std::mem::transmute(raw.offset(4))
+            }
+            pub unsafe fn i(&mut self) -> *mut ::std::os::raw::c_int {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_1_);
+                ::std::mem::transmute(raw.offset(8))
+            }
+            pub unsafe fn f(&mut self) -> *mut ::std::os::raw::c_float {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_1_);
+                ::std::mem::transmute(raw.offset(8))
+            }
+        }
+        impl ::std::default::Default for outer {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+
+    #[repr(C)]
+    struct Inner {
+        flag: u8,
+        i: i32,
+    }
+    #[repr(C)]
+    struct Middle {
+        kind: i16,
+        inner: Inner,
+    }
+    #[repr(C)]
+    struct Outer {
+        tag: u8,
+        middle: Middle,
+    }
+    let outer: Outer = unsafe { mem::zeroed() };
+    let base = &outer.middle as *const Middle as usize;
+    assert_eq!(&outer.middle.inner.flag as *const u8 as usize - base, 4);
+    assert_eq!(&outer.middle.inner.i as *const i32 as usize - base, 8);
+}
+
+#[test]
+fn with_associated_initializer() {
+    let mut options: BindgenOptions = Default::default();
+    options.initializers.push(("buffer".to_owned(), "buffer_init".to_owned()));
+    assert_bind_eq(options, "headers/struct_with_initializer.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct buffer {
+            pub len: ::std::os::raw::c_int,
+            pub data: [::std::os::raw::c_char; 16usize],
+        }
+        impl ::std::default::Default for buffer {
+            fn default() -> Self {
+                unsafe {
+                    let mut value: Self = ::std::mem::zeroed();
+                    buffer_init(&mut value);
+                    value
+                }
+            }
+        }
+        extern \"C\" {
+            pub fn buffer_init(b: *mut buffer);
+        }
+    ");
+}
+
+#[test]
+fn with_associated_initializer_in_module() {
+    let mut options: BindgenOptions = Default::default();
+    options.initializers.push(("buffer".to_owned(), "buffer_init".to_owned()));
+    options.function_prefix_modules.push(("buffer_".to_owned(), "buffers".to_owned()));
+    assert_bind_eq(options, "headers/struct_with_initializer.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct buffer {
+            pub len: ::std::os::raw::c_int,
+            pub data: [::std::os::raw::c_char; 16usize],
+        }
+        impl ::std::default::Default for buffer {
+            fn default() -> Self {
+                unsafe {
+                    let mut value: Self = ::std::mem::zeroed();
+                    buffers::init(&mut value);
+                    value
+                }
+            }
+        }
+        pub mod buffers {
+            #[allow(unused_imports)]
+            use super::*;
+            extern \"C\" {
+                #[link_name = \"buffer_init\"]
+                pub fn init(b: *mut buffer);
+            }
+        }
+    ");
+}
+
+#[test]
+fn with_associated_initializer_not_generated() {
+    let logger = CollectingLogger::new();
+    let bindings = bindgen::builder()
+        .header("tests/headers/struct_with_initializer.h")
+        .associate_initializer("buffer", "buffer_init")
+        .emit_functions(false)
+        .log(&logger)
+        .generate()
+        .unwrap()
+        .to_string();
+
+    assert!(!bindings.contains("buffer_init"));
+    assert!(bindings.contains("unsafe { ::std::mem::zeroed() }"));
+    assert!(logger.warnings
+                  .borrow()
+                  .iter()
+                  .any(|w| w.contains("the initializer `buffer_init` of `buffer` isn't generated")));
+}
@@ -8,3 +8,107 @@ fn typedef_same_name() {
         pub enum SameU {  }
     ");
 }
+
+#[test]
+fn composed_declarators() {
+    assert_bind_eq(Default::default(), "headers/typedef_composed_declarators.h", "
+        pub type str_fn = ::std::option::Option<extern \"C\" fn() -> *mut ::std::os::raw::c_char>;
+        pub type name_fn = ::std::option::Option<
+            unsafe extern \"C\" fn(arg1: *const ::std::os::raw::c_void)
+                                  -> *const ::std::os::raw::c_char>;
+        pub type ptr_array = [*mut ::std::os::raw::c_int; 3usize];
+    ");
+}
+
+#[test]
+fn typeof_typedef() {
+    assert_bind_eq(Default::default(), "headers/typedef_typeof.h", "
+        pub type my_int = ::std::os::raw::c_int;
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct holder {
+            pub value: ::std::os::raw::c_long,
+        }
+        impl ::std::default::Default for holder {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn decltype_typedef() {
+    assert_bind_eq(Default::default(), "headers/typedef_decltype.hpp", "
+        pub type my_double = ::std::os::raw::c_double;
+    ");
+}
+
+#[test]
+fn distinct_opaque_handles() {
+    assert_bind_eq(Default::default(), "headers/typedef_opaque_handles.h", "
+        pub enum a_ {  }
+        pub type A = *mut a_;
+        pub enum b_ {  }
+        pub type B = *mut b_;
+    ");
+}
+
+#[test]
+fn variadic_fn_ptr_typedef() {
+    assert_bind_eq(Default::default(), "headers/typedef_variadic_fn_ptr.h", "
+        pub type printf_t = ::std::option::Option<
+            unsafe extern \"C\" fn(fmt: *const ::std::os::raw::c_char, ...)
+                                  -> ::std::os::raw::c_int>;
+    ");
+}
+
+#[test]
+fn fn_ptr_with_anon_enum_param() {
+    assert_bind_eq(Default::default(), "headers/typedef_fn_ptr_anon_enum.h", "
+        #[derive(Copy, Clone)]
+        #[repr(u32)]
+        #[derive(Debug)]
+        pub enum Enum_Unnamed1 { OK = 0, ERR = 1, }
+        pub type cb = ::std::option::Option<extern \"C\" fn(status: Enum_Unnamed1)>;
+    ");
+}
+
+#[test]
+fn anon_struct_with_several_names() {
+    assert_bind_eq(Default::default(), "headers/typedef_anon_struct_names.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct A {
+            pub x: ::std::os::raw::c_int,
+        }
+        impl ::std::default::Default for A {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+        pub type B = A;
+    ");
+}
+
+#[test]
+fn array_newtype_indexing() {
+    let mut options: ::bindgen::BindgenOptions = Default::default();
+    options.array_newtype_indexing = true;
+    assert_bind_eq(options, "headers/typedef_array_vec4.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        #[derive(Debug)]
+        pub struct vec4(pub [::std::os::raw::c_float; 4usize]);
+        impl ::std::ops::Index<usize> for vec4 {
+            type Output = ::std::os::raw::c_float;
+            fn index(&self, index: usize) -> &::std::os::raw::c_float { &self.0[index] }
+        }
+        impl ::std::ops::IndexMut<usize> for vec4 {
+            fn index_mut(&mut self, index: usize) -> &mut ::std::os::raw::c_float {
+                &mut self.0[index]
+            }
+        }
+        extern \"C\" {
+            pub fn vec4_normalize(v: *mut vec4) -> *mut vec4;
+        }
+    ");
+}
@@ -1,3 +1,4 @@
+use bindgen::BindgenOptions;
 use support::assert_bind_eq;
 
 #[test]
@@ -5,7 +6,6 @@ fn with_anon_struct() {
     assert_bind_eq(Default::default(), "headers/union_with_anon_struct.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct foo {
             pub _bindgen_data_: [u32; 2usize],
         }
@@ -15,6 +15,15 @@ fn with_anon_struct() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"foo\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -36,7 +45,6 @@ fn with_anon_struct_bitfield() {
     assert_bind_eq(Default::default(), "headers/union_with_anon_struct_bitfield.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct foo {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -47,6 +55,15 @@ fn with_anon_struct_bitfield() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"foo\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -58,7 +75,6 @@ fn with_anon_union() {
     assert_bind_eq(Default::default(), "headers/union_with_anon_union.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct foo {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -68,12 +84,20 @@ fn with_anon_union() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"foo\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct Union_Unnamed1 {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -87,6 +111,15 @@ fn with_anon_union() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for Union_Unnamed1 {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"Union_Unnamed1\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for Union_Unnamed1 {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -98,7 +131,6 @@ fn with_anon_unnamed_struct() {
     assert_bind_eq(Default::default(), "headers/union_with_anon_unnamed_struct.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct pixel {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -124,6 +156,15 @@ fn with_anon_unnamed_struct() {
                 ::std::mem::transmute(raw.offset(3))
             }
         }
+        impl ::std::fmt::Debug for pixel {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"pixel\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for pixel {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -135,7 +176,6 @@ fn with_anon_unnamed_union() {
     assert_bind_eq(Default::default(), "headers/union_with_anon_unnamed_union.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct foo {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -153,6 +193,15 @@ fn with_anon_unnamed_union() {
                 ::std::mem::transmute(raw.offset(0))
             }
         }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"foo\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -164,7 +213,6 @@ fn with_nesting() {
     assert_bind_eq(Default::default(), "headers/union_with_nesting.h", "
         #[repr(C)]
         #[derive(Copy, Clone)]
-        #[derive(Debug)]
         pub struct foo {
             pub _bindgen_data_: [u32; 1usize],
         }
@@ -190,6 +238,15 @@ fn with_nesting() {
                 ::std::mem::transmute(raw.offset(2))
             }
         }
+        impl ::std::fmt::Debug for foo {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"foo\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for foo {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -217,6 +274,15 @@ fn with_derive_debug() {
         impl ::std::clone::Clone for WithBigArray {
             fn clone(&self) -> Self { *self }
         }
+        impl ::std::fmt::Debug for WithBigArray {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"WithBigArray\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for WithBigArray {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
@@ -238,8 +304,82 @@ fn with_derive_debug() {
         impl ::std::clone::Clone for WithBigMember {
             fn clone(&self) -> Self { *self }
         }
+        impl ::std::fmt::Debug for WithBigMember {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"WithBigMember\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
         impl ::std::default::Default for WithBigMember {
             fn default() -> Self { unsafe { ::std::mem::zeroed() } }
         }
     ");
 }
+
+#[test]
+fn without_derive_debug() {
+    let mut options: BindgenOptions = Default::default();
+    options.derive_debug = false;
+    assert_bind_eq(options, "headers/union_with_anon_struct_bitfield.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct foo {
+            pub _bindgen_data_: [u32; 1usize],
+        }
+        impl foo {
+            pub unsafe fn a(&mut self) -> *mut ::std::os::raw::c_int {
+                let raw: *mut u8 = ::std::mem::transmute(&self._bindgen_data_);
+                ::std::mem::transmute(raw.offset(0))
+            }
+        }
+        impl ::std::default::Default for foo {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
+
+#[test]
+fn with_access_by_value() {
+    let mut options: BindgenOptions = Default::default();
+    options.union_access_by_value = true;
+    assert_bind_eq(options, "headers/union_int_float.h", "
+        #[repr(C)]
+        #[derive(Copy, Clone)]
+        pub struct number {
+            pub _bindgen_data_: [u32; 1usize],
+        }
+        impl number {
+            pub unsafe fn i(&self) -> ::std::os::raw::c_int {
+                let raw: *const u8 = ::std::mem::transmute(&self._bindgen_data_);
+                ::std::ptr::read(raw.offset(0) as *const ::std::os::raw::c_int)
+            }
+            pub unsafe fn set_i(&mut self, value: ::std::os::raw::c_int) {
+                let raw: *mut u8 = ::std::mem::transmute(&mut self._bindgen_data_);
+                ::std::ptr::write(raw.offset(0) as *mut ::std::os::raw::c_int, value)
+            }
+            pub unsafe fn f(&self) -> ::std::os::raw::c_float {
+                let raw: *const u8 = ::std::mem::transmute(&self._bindgen_data_);
+                ::std::ptr::read(raw.offset(0) as *const ::std::os::raw::c_float)
+            }
+            pub unsafe fn set_f(&mut self, value: ::std::os::raw::c_float) {
+                let raw: *mut u8 = ::std::mem::transmute(&mut self._bindgen_data_);
+                ::std::ptr::write(raw.offset(0) as *mut ::std::os::raw::c_float, value)
+            }
+        }
+        impl ::std::fmt::Debug for number {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                let bytes: &[u8] = unsafe {
+                    ::std::slice::from_raw_parts(self as *const Self as *const u8,
+                                                 ::std::mem::size_of::<Self>())
+                };
+                f.debug_struct(\"number\").field(\"_bindgen_data_\", &bytes).finish()
+            }
+        }
+        impl ::std::default::Default for number {
+            fn default() -> Self { unsafe { ::std::mem::zeroed() } }
+        }
+    ");
+}
@@ -16,3 +16,6 @@ mod test_union;
 mod test_builtins;
 mod test_ints;
 mod test_typedef;
+mod test_diagnostics;
+mod test_macro;
+mod test_output;